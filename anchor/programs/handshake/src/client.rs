@@ -0,0 +1,515 @@
+//! Off-chain helpers for building handshake instructions from Rust clients.
+//!
+//! Each builder derives the PDAs and associated token accounts the program
+//! expects and returns a ready [`Instruction`], mirroring the account structs
+//! in `instructions/`. Builders take the instruction's remaining accounts
+//! (e.g. the extra accounts of a Token-2022 transfer hook) and append them
+//! after its named accounts. Enabled with the `client` feature.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use anchor_spl::memo::Memo;
+use solana_sdk_ids::sysvar::instructions as instructions_sysvar;
+use crate::constants::*;
+
+pub use crate::instructions::FeeCoupon;
+
+/// Pool PDA for `pool_id`
+pub fn find_pool_address(pool_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[POOL_SEED, pool_id.as_ref()], &crate::ID).0
+}
+
+/// Transfer PDA for a (sender, recipient, nonce) triple. The address keeps
+/// deriving from the original recipient after `update_recipient` redirects it.
+pub fn find_transfer_address(sender: &Pubkey, recipient: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SENDER_SEED,
+            sender.as_ref(),
+            RECIPIENT_SEED,
+            recipient.as_ref(),
+            NONCE_SEED,
+            nonce.to_le_bytes().as_ref(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Recipient expectation PDA in `pool`
+pub fn find_expectation_address(pool: &Pubkey, recipient: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EXPECTATION_SEED, pool.as_ref(), recipient.as_ref()], &crate::ID).0
+}
+
+/// Coupon redemption PDA for `sender` in `pool`
+pub fn find_coupon_redemption_address(pool: &Pubkey, sender: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[COUPON_SEED, pool.as_ref(), sender.as_ref()], &crate::ID).0
+}
+
+/// Deferred refund PDA for `transfer`
+pub fn find_pending_refund_address(transfer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[REFUND_SEED, transfer.as_ref()], &crate::ID).0
+}
+
+/// Fee vault PDA for `pool`
+pub fn find_fee_vault_address(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FEE_VAULT_SEED, pool.as_ref()], &crate::ID).0
+}
+
+/// Sender profile PDA for `sender` in `pool`
+pub fn find_sender_profile_address(pool: &Pubkey, sender: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[SENDER_PROFILE_SEED, pool.as_ref(), sender.as_ref()], &crate::ID).0
+}
+
+/// Append an instruction's remaining accounts after its named accounts
+fn with_remaining(mut metas: Vec<AccountMeta>, remaining_accounts: &[AccountMeta]) -> Vec<AccountMeta> {
+    metas.extend_from_slice(remaining_accounts);
+    metas
+}
+
+/// Identifies a pool and the token program of its mint
+#[derive(Clone, Copy, Debug)]
+pub struct PoolRef {
+    pub pool_id: Pubkey,
+    pub mint: Pubkey,
+    /// SPL Token or Token-2022, whichever owns the mint
+    pub token_program: Pubkey,
+}
+
+impl PoolRef {
+    pub fn address(&self) -> Pubkey {
+        find_pool_address(&self.pool_id)
+    }
+
+    /// Associated token account of `owner` for the pool mint
+    pub fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+}
+
+/// Parameters for `create_transfer`; see the instruction for their semantics
+#[derive(Clone, Debug, Default)]
+pub struct CreateTransferParams {
+    pub recipient: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub memo: String,
+    pub claimable_after: i64,
+    pub claimable_until: i64,
+    pub claim_code_hash: Option<[u8; 32]>,
+    pub keeper_tip: u64,
+    pub refund_token_account: Option<Pubkey>,
+    pub requires_mutual: bool,
+    /// Must be preceded in the transaction by the coupon signer's Ed25519 instruction
+    pub coupon: Option<(u16, i64)>,
+    pub exact_amount: Option<u64>,
+    /// Highest SOL fee the sender accepts when the pool charges fees in SOL
+    pub max_sol_fee_lamports: u64,
+    /// Count the transfer towards the sender's free-transfer allowance (profile must exist)
+    pub sender_profile: bool,
+}
+
+/// Build `create_transfer` for `sender` in `pool`
+pub fn create_transfer(
+    pool: &PoolRef,
+    sender: &Pubkey,
+    params: CreateTransferParams,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let pool_address = pool.address();
+    let accounts = crate::accounts::CreateTransfer {
+        sender: *sender,
+        pool: pool_address,
+        mint: pool.mint,
+        pool_token_account: pool.token_account(&pool_address),
+        sender_token_account: pool.token_account(sender),
+        transfer: find_transfer_address(sender, &params.recipient, params.nonce),
+        recipient_expectation: find_expectation_address(&pool_address, &params.recipient),
+        instructions_sysvar: params.coupon.map(|_| instructions_sysvar::ID),
+        coupon_redemption: params
+            .coupon
+            .map(|_| find_coupon_redemption_address(&pool_address, sender)),
+        refund_token_account: params.refund_token_account,
+        sender_profile: params
+            .sender_profile
+            .then(|| find_sender_profile_address(&pool_address, sender)),
+        token_program: pool.token_program,
+        system_program: anchor_lang::system_program::ID,
+        associated_token_program: associated_token::ID,
+    };
+    let data = crate::instruction::CreateTransfer {
+        recipient: params.recipient,
+        nonce: params.nonce,
+        amount: params.amount,
+        memo: params.memo,
+        claimable_after: params.claimable_after,
+        claimable_until: params.claimable_until,
+        claim_code_hash: params.claim_code_hash,
+        keeper_tip: params.keeper_tip,
+        refund_token_account: params.refund_token_account,
+        requires_mutual: params.requires_mutual,
+        coupon: params
+            .coupon
+            .map(|(discount_bps, expiry)| FeeCoupon { discount_bps, expiry }),
+        exact_amount: params.exact_amount,
+        max_sol_fee_lamports: params.max_sol_fee_lamports,
+    };
+    Instruction {
+        program_id: crate::ID,
+        accounts: with_remaining(accounts.to_account_metas(None), remaining_accounts),
+        data: data.data(),
+    }
+}
+
+/// Optional accounts `claim_transfer` needs depending on pool and transfer config
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClaimOptions {
+    /// The pool has a fee vault
+    pub fee_vault: bool,
+    /// The pool has notify_memo set
+    pub memo: bool,
+    /// The transfer has an exact amount, so change is returned to the sender's ATA
+    pub sender_change: bool,
+}
+
+/// Build `claim_transfer` of `transfer` by its current `recipient`
+pub fn claim_transfer(
+    pool: &PoolRef,
+    sender: &Pubkey,
+    recipient: &Pubkey,
+    transfer: &Pubkey,
+    claim_code: Option<[u8; 32]>,
+    options: ClaimOptions,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let pool_address = pool.address();
+    let accounts = crate::accounts::ClaimTransfer {
+        recipient: *recipient,
+        pool: pool_address,
+        mint: pool.mint,
+        pool_token_account: pool.token_account(&pool_address),
+        recipient_token_account: pool.token_account(recipient),
+        sender_token_account: options.sender_change.then(|| pool.token_account(sender)),
+        transfer: *transfer,
+        sender: *sender,
+        fee_vault: options.fee_vault.then(|| find_fee_vault_address(&pool_address)),
+        memo_program: options.memo.then(Memo::id),
+        token_program: pool.token_program,
+    };
+    Instruction {
+        program_id: crate::ID,
+        accounts: with_remaining(accounts.to_account_metas(None), remaining_accounts),
+        data: crate::instruction::ClaimTransfer { claim_code }.data(),
+    }
+}
+
+/// Build `cancel_transfer` of `transfer` by its `sender`.
+/// `sender_profile` gives a fee-waived transfer's allowance back (profile must exist).
+pub fn cancel_transfer(
+    pool: &PoolRef,
+    sender: &Pubkey,
+    transfer: &Pubkey,
+    sender_profile: bool,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let pool_address = pool.address();
+    let accounts = crate::accounts::CancelTransfer {
+        sender: *sender,
+        pool: pool_address,
+        mint: pool.mint,
+        pool_token_account: pool.token_account(&pool_address),
+        sender_token_account: pool.token_account(sender),
+        transfer: *transfer,
+        sender_profile: sender_profile.then(|| find_sender_profile_address(&pool_address, sender)),
+        token_program: pool.token_program,
+    };
+    Instruction {
+        program_id: crate::ID,
+        accounts: with_remaining(accounts.to_account_metas(None), remaining_accounts),
+        data: crate::instruction::CancelTransfer {}.data(),
+    }
+}
+
+/// Where `reject_transfer` sends the refund
+#[derive(Clone, Copy, Debug)]
+pub enum RejectRefund {
+    /// The sender's ATA
+    SenderAta,
+    /// The refund account override recorded on the transfer
+    Override(Pubkey),
+    /// Defer into a PendingRefund (sender's ATA is missing or frozen)
+    Deferred,
+    /// Defer into a PendingRefund (the recorded override is closed or frozen)
+    OverrideDeferred(Pubkey),
+}
+
+/// Build `reject_transfer` of `transfer` by `operator`.
+/// `sender_profile` gives a fee-waived transfer's allowance back (profile must exist).
+#[allow(clippy::too_many_arguments)]
+pub fn reject_transfer(
+    pool: &PoolRef,
+    operator: &Pubkey,
+    sender: &Pubkey,
+    transfer: &Pubkey,
+    reason: Option<u8>,
+    refund: RejectRefund,
+    sender_profile: bool,
+    remaining_accounts: &[AccountMeta],
+) -> Instruction {
+    let pool_address = pool.address();
+    let deferred = matches!(refund, RejectRefund::Deferred | RejectRefund::OverrideDeferred(_));
+    let accounts = crate::accounts::RejectTransfer {
+        operator: *operator,
+        pool: pool_address,
+        mint: pool.mint,
+        pool_token_account: pool.token_account(&pool_address),
+        sender_token_account: pool.token_account(sender),
+        refund_token_account: match refund {
+            RejectRefund::Override(account) | RejectRefund::OverrideDeferred(account) => Some(account),
+            _ => None,
+        },
+        pending_refund: deferred.then(|| find_pending_refund_address(transfer)),
+        transfer: *transfer,
+        sender: *sender,
+        sender_profile: sender_profile.then(|| find_sender_profile_address(&pool_address, sender)),
+        token_program: pool.token_program,
+        system_program: deferred.then_some(anchor_lang::system_program::ID),
+    };
+    Instruction {
+        program_id: crate::ID,
+        accounts: with_remaining(accounts.to_account_metas(None), remaining_accounts),
+        data: crate::instruction::RejectTransfer { reason }.data(),
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    fn pool() -> PoolRef {
+        PoolRef {
+            pool_id: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            token_program: anchor_spl::token::ID,
+        }
+    }
+
+    /// Anchor's instruction discriminator: the first 8 bytes of sha256("global:<name>")
+    fn sighash(name: &str) -> [u8; 8] {
+        let hash = solana_sha256_hasher::hash(format!("global:{name}").as_bytes());
+        hash.to_bytes()[..8].try_into().unwrap()
+    }
+
+    /// Placeholder Anchor passes for an omitted optional account
+    fn omitted() -> AccountMeta {
+        AccountMeta::new_readonly(crate::ID, false)
+    }
+
+    #[test]
+    fn pdas_match_the_program_seeds() {
+        let pool_id = Pubkey::new_unique();
+        let pool = find_pool_address(&pool_id);
+        let (sender, recipient, nonce) = (Pubkey::new_unique(), Pubkey::new_unique(), 7u64);
+        let derive = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0;
+
+        assert_eq!(pool, derive(&[b"pool", pool_id.as_ref()]));
+        assert_eq!(
+            find_transfer_address(&sender, &recipient, nonce),
+            derive(&[
+                b"sender",
+                sender.as_ref(),
+                b"recipient",
+                recipient.as_ref(),
+                b"nonce",
+                &nonce.to_le_bytes(),
+            ])
+        );
+        assert_eq!(
+            find_expectation_address(&pool, &recipient),
+            derive(&[b"expectation", pool.as_ref(), recipient.as_ref()])
+        );
+        assert_eq!(
+            find_coupon_redemption_address(&pool, &sender),
+            derive(&[b"coupon", pool.as_ref(), sender.as_ref()])
+        );
+        let transfer = find_transfer_address(&sender, &recipient, nonce);
+        assert_eq!(find_pending_refund_address(&transfer), derive(&[b"refund", transfer.as_ref()]));
+        assert_eq!(find_fee_vault_address(&pool), derive(&[b"fee_vault", pool.as_ref()]));
+        assert_eq!(
+            find_sender_profile_address(&pool, &sender),
+            derive(&[b"sender_profile", pool.as_ref(), sender.as_ref()])
+        );
+    }
+
+    #[test]
+    fn discriminators_match_the_idl() {
+        // Values from the published IDL (apps/backend/src/solana/handshake-idl.json)
+        let cases: [(&str, &[u8], [u8; 8]); 4] = [
+            ("create_transfer", crate::instruction::CreateTransfer::DISCRIMINATOR, [142, 232, 86, 212, 85, 158, 131, 190]),
+            ("claim_transfer", crate::instruction::ClaimTransfer::DISCRIMINATOR, [202, 178, 58, 190, 230, 234, 229, 17]),
+            ("cancel_transfer", crate::instruction::CancelTransfer::DISCRIMINATOR, [50, 32, 70, 130, 142, 41, 111, 175]),
+            ("reject_transfer", crate::instruction::RejectTransfer::DISCRIMINATOR, [250, 250, 180, 34, 151, 19, 110, 207]),
+        ];
+        for (name, discriminator, idl) in cases {
+            assert_eq!(discriminator, idl, "{name}");
+            assert_eq!(sighash(name), idl, "{name}");
+        }
+
+        let (pool, sender, recipient) = (pool(), Pubkey::new_unique(), Pubkey::new_unique());
+        let transfer = find_transfer_address(&sender, &recipient, 0);
+        let built = [
+            create_transfer(&pool, &sender, CreateTransferParams::default(), &[]),
+            claim_transfer(&pool, &sender, &recipient, &transfer, None, ClaimOptions::default(), &[]),
+            cancel_transfer(&pool, &sender, &transfer, false, &[]),
+            reject_transfer(&pool, &pool.pool_id, &sender, &transfer, None, RejectRefund::SenderAta, false, &[]),
+        ];
+        for ((name, _, idl), ix) in cases.iter().zip(built) {
+            assert_eq!(ix.program_id, crate::ID);
+            assert_eq!(ix.data[..8], idl[..], "{name}");
+        }
+    }
+
+    #[test]
+    fn create_transfer_account_metas() {
+        let pool = pool();
+        let pool_address = pool.address();
+        let (sender, recipient, refund) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let params = CreateTransferParams {
+            recipient,
+            nonce: 3,
+            amount: 1_000,
+            refund_token_account: Some(refund),
+            coupon: Some((500, 0)),
+            sender_profile: true,
+            max_sol_fee_lamports: 5_000,
+            ..Default::default()
+        };
+        let ix = create_transfer(&pool, &sender, params, &[]);
+
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(sender, true),
+                AccountMeta::new(pool_address, false),
+                AccountMeta::new_readonly(pool.mint, false),
+                AccountMeta::new(pool.token_account(&pool_address), false),
+                AccountMeta::new(pool.token_account(&sender), false),
+                AccountMeta::new(find_transfer_address(&sender, &recipient, 3), false),
+                AccountMeta::new_readonly(find_expectation_address(&pool_address, &recipient), false),
+                AccountMeta::new_readonly(instructions_sysvar::ID, false),
+                AccountMeta::new(find_coupon_redemption_address(&pool_address, &sender), false),
+                AccountMeta::new_readonly(refund, false),
+                AccountMeta::new(find_sender_profile_address(&pool_address, &sender), false),
+                AccountMeta::new_readonly(pool.token_program, false),
+                AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+                AccountMeta::new_readonly(associated_token::ID, false),
+            ]
+        );
+
+        let args = crate::instruction::CreateTransfer::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.recipient, recipient);
+        assert_eq!(args.nonce, 3);
+        assert_eq!(args.refund_token_account, Some(refund));
+        assert_eq!(args.coupon.map(|coupon| coupon.discount_bps), Some(500));
+        assert_eq!(args.max_sol_fee_lamports, 5_000);
+    }
+
+    #[test]
+    fn claim_transfer_account_metas() {
+        let pool = pool();
+        let pool_address = pool.address();
+        let (sender, recipient) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let transfer = find_transfer_address(&sender, &recipient, 1);
+
+        let ix = claim_transfer(&pool, &sender, &recipient, &transfer, None, ClaimOptions::default(), &[]);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(recipient, true),
+                AccountMeta::new(pool_address, false),
+                AccountMeta::new(pool.mint, false),
+                AccountMeta::new(pool.token_account(&pool_address), false),
+                AccountMeta::new(pool.token_account(&recipient), false),
+                omitted(),
+                AccountMeta::new(transfer, false),
+                AccountMeta::new(sender, false),
+                omitted(),
+                omitted(),
+                AccountMeta::new_readonly(pool.token_program, false),
+            ]
+        );
+
+        let options = ClaimOptions { fee_vault: true, memo: true, sender_change: true };
+        let ix = claim_transfer(&pool, &sender, &recipient, &transfer, None, options, &[]);
+        assert_eq!(ix.accounts[5], AccountMeta::new(pool.token_account(&sender), false));
+        assert_eq!(ix.accounts[8], AccountMeta::new(find_fee_vault_address(&pool_address), false));
+        assert_eq!(ix.accounts[9], AccountMeta::new_readonly(Memo::id(), false));
+    }
+
+    #[test]
+    fn claim_transfer_after_the_recipient_is_redirected() {
+        let pool = pool();
+        let (sender, original, redirected) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let transfer = find_transfer_address(&sender, &original, 5);
+
+        let ix = claim_transfer(&pool, &sender, &redirected, &transfer, None, ClaimOptions::default(), &[]);
+        // The new recipient signs and is paid; the transfer keeps its original address
+        assert_eq!(ix.accounts[0], AccountMeta::new(redirected, true));
+        assert_eq!(ix.accounts[4], AccountMeta::new(pool.token_account(&redirected), false));
+        assert_eq!(ix.accounts[6], AccountMeta::new(transfer, false));
+        assert_ne!(transfer, find_transfer_address(&sender, &redirected, 5));
+    }
+
+    #[test]
+    fn reject_transfer_account_metas() {
+        let pool = pool();
+        let pool_address = pool.address();
+        let (operator, sender, recipient) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let transfer = find_transfer_address(&sender, &recipient, 2);
+
+        let ix = reject_transfer(&pool, &operator, &sender, &transfer, Some(1), RejectRefund::Deferred, true, &[]);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(operator, true),
+                AccountMeta::new(pool_address, false),
+                AccountMeta::new_readonly(pool.mint, false),
+                AccountMeta::new(pool.token_account(&pool_address), false),
+                AccountMeta::new(pool.token_account(&sender), false),
+                omitted(),
+                AccountMeta::new(find_pending_refund_address(&transfer), false),
+                AccountMeta::new(transfer, false),
+                AccountMeta::new(sender, false),
+                AccountMeta::new(find_sender_profile_address(&pool_address, &sender), false),
+                AccountMeta::new_readonly(pool.token_program, false),
+                AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+            ]
+        );
+        let args = crate::instruction::RejectTransfer::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(args.reason, Some(1));
+
+        let refund = Pubkey::new_unique();
+        let ix = reject_transfer(&pool, &operator, &sender, &transfer, None, RejectRefund::OverrideDeferred(refund), false, &[]);
+        assert_eq!(ix.accounts[5], AccountMeta::new(refund, false));
+        assert_eq!(ix.accounts[6], AccountMeta::new(find_pending_refund_address(&transfer), false));
+    }
+
+    #[test]
+    fn remaining_accounts_follow_the_named_accounts() {
+        let pool = pool();
+        let transfer = find_transfer_address(&Pubkey::new_unique(), &Pubkey::new_unique(), 4);
+        let sender = Pubkey::new_unique();
+        let hook_accounts = [
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+        ];
+
+        let plain = cancel_transfer(&pool, &sender, &transfer, false, &[]);
+        let hooked = cancel_transfer(&pool, &sender, &transfer, false, &hook_accounts);
+        assert_eq!(hooked.accounts[..plain.accounts.len()], plain.accounts[..]);
+        assert_eq!(hooked.accounts[plain.accounts.len()..], hook_accounts[..]);
+    }
+}
@@ -3,3 +3,63 @@ pub const POOL_SEED: &[u8] = b"pool";
 pub const SENDER_SEED: &[u8] = b"sender";
 pub const RECIPIENT_SEED: &[u8] = b"recipient";
 pub const NONCE_SEED: &[u8] = b"nonce";
+pub const EXPECTATION_SEED: &[u8] = b"expectation";
+pub const CANONICAL_POOL_SEED: &[u8] = b"canonical_pool";
+pub const REFUND_SEED: &[u8] = b"refund";
+pub const COUPON_SEED: &[u8] = b"coupon";
+pub const STAKE_SEED: &[u8] = b"stake";
+pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+pub const RECONCILE_SEED: &[u8] = b"reconcile";
+pub const POOL_METADATA_SEED: &[u8] = b"pool_metadata";
+pub const SENDER_PROFILE_SEED: &[u8] = b"sender_profile";
+
+// Share of a transfer (in bps) the fee can never eat into, whatever the pool's fee config
+pub const MIN_REFUND_BPS: u16 = 9000;
+
+// Current Pool layout version, set at init and by migrate_pool
+pub const POOL_VERSION: u8 = 2;
+
+// Current SecureTransfer layout version, set at creation and by migrate_transfer
+pub const TRANSFER_VERSION: u8 = 2;
+
+// Minimum seconds between transfer fee changes on a pool, also applied to
+// pools whose fee_change_cooldown reads 0 (migrated from the older layout)
+pub const DEFAULT_FEE_CHANGE_COOLDOWN: i64 = 24 * 60 * 60;
+
+// Bounds on a fee change cooldown set by the operator
+pub const MIN_FEE_CHANGE_COOLDOWN: i64 = 60 * 60;
+pub const MAX_FEE_CHANGE_COOLDOWN: i64 = 30 * 24 * 60 * 60;
+
+// Maximum SOL fee (lamports) a pool can charge per transfer
+pub const MAX_SOL_FEE_LAMPORTS: u64 = 1_000_000_000;
+
+// Maximum number of items processed by a single batch instruction.
+// The program can't raise its own compute budget, so batches are capped to
+// fit the default 200k CU. Each item is a transfer_checked CPI, one
+// create_program_address (plus an ATA derivation when sweeping fees) and an
+// account (de)serialization. Tests M4 and AN3 run a full batch, read
+// computeUnitsConsumed, and fail if the batch or its per-item cost
+// (200k / MAX_BATCH_SIZE) no longer fits; revisit this cap if they do.
+pub const MAX_BATCH_SIZE: usize = 8;
+
+// Maximum number of milestones a transfer can be released in
+pub const MAX_MILESTONES: usize = 4;
+
+// Maximum number of destinations a reject refund can be split across
+pub const MAX_REFUND_SPLITS: usize = 4;
+
+// Pool expiry_behavior values: what expire_transfer does with an abandoned transfer
+pub const EXPIRY_REFUND_SENDER: u8 = 0;
+pub const EXPIRY_FORWARD_RECIPIENT: u8 = 1;
+
+// Pool event_verbosity values: minimal events omit memos and reject reasons.
+// FULL is 0 so pools whose zeroed padding became this field keep full events.
+pub const EVENT_VERBOSITY_FULL: u8 = 0;
+pub const EVENT_VERBOSITY_MINIMAL: u8 = 1;
+
+// Seconds an accounting correction must wait between proposal and execution
+pub const RECONCILE_TIMELOCK: i64 = 2 * 24 * 60 * 60;
+
+// Maximum byte lengths of a pool's display name and metadata URI
+pub const MAX_POOL_NAME_LEN: usize = 32;
+pub const MAX_POOL_URI_LEN: usize = 128;
@@ -91,4 +91,160 @@ pub enum HandshakeError {
 
     #[msg("Only recipient can decline transfer")]
     OnlyRecipientCanDecline,
+
+    #[msg("Fee was changed too recently")]
+    FeeChangeCooldown,
+
+    #[msg("Invalid claim code")]
+    InvalidClaimCode,
+
+    #[msg("Batch exceeds maximum size")]
+    BatchTooLarge,
+
+    #[msg("Invalid remaining accounts")]
+    InvalidRemainingAccounts,
+
+    #[msg("Amount is below the recipient's expected amount")]
+    AmountBelowExpected,
+
+    #[msg("Missing or invalid Ed25519 signature instruction")]
+    InvalidSignature,
+
+    #[msg("Operator nonce does not match the pool")]
+    InvalidOperatorNonce,
+
+    #[msg("Keeper tip must be less than the transfer amount")]
+    InvalidKeeperTip,
+
+    #[msg("Transfer amount has not been revealed")]
+    AmountNotRevealed,
+
+    #[msg("Revealed amount does not match the commitment")]
+    InvalidAmountReveal,
+
+    #[msg("Settlement exceeds the transfer amount")]
+    InvalidSettlement,
+
+    #[msg("Pool does not allow deferred refunds")]
+    RefundDeferralDisabled,
+
+    #[msg("Pending refund account missing or unexpected")]
+    InvalidPendingRefund,
+
+    #[msg("Refund token account does not match the transfer")]
+    InvalidRefundAccount,
+
+    #[msg("Mint has a transfer hook and the pool does not allow them")]
+    TransferHookNotAllowed,
+
+    #[msg("Invalid expiry behavior")]
+    InvalidExpiryBehavior,
+
+    #[msg("Recipient token account is required")]
+    RecipientTokenAccountRequired,
+
+    #[msg("Invalid event verbosity")]
+    InvalidEventVerbosity,
+
+    #[msg("Pool token account does not back the escrowed amount")]
+    EscrowMismatch,
+
+    #[msg("Transfer requires mutual acceptance by operator and recipient")]
+    MutualAcceptRequired,
+
+    #[msg("Transfer has outlived the pool's max lifetime and must be force-resolved")]
+    TransferStale,
+
+    #[msg("Transfer has not outlived the pool's max lifetime")]
+    TransferNotStale,
+
+    #[msg("Transfer is not awaiting a reject undo or finalization")]
+    TransferNotRejectPending,
+
+    #[msg("Reject undo window is still open")]
+    RejectUndoWindowOpen,
+
+    #[msg("Reject undo window has closed")]
+    RejectUndoWindowClosed,
+
+    #[msg("Invalid fee coupon")]
+    InvalidCoupon,
+
+    #[msg("Fee coupon has expired")]
+    CouponExpired,
+
+    #[msg("Token account is not the pool's fee destination")]
+    InvalidFeeAccount,
+
+    #[msg("Sender and recipient are the same")]
+    SelfTransfer,
+
+    #[msg("Operator stake is below the pool minimum")]
+    InsufficientOperatorStake,
+
+    #[msg("Operator stake must be withdrawn first")]
+    OperatorStakeOutstanding,
+
+    #[msg("Transfer is released by milestones")]
+    MilestonesPending,
+
+    #[msg("Invalid milestones")]
+    InvalidMilestones,
+
+    #[msg("Milestone already released")]
+    MilestoneAlreadyReleased,
+
+    #[msg("Token account is not owned by the expected party")]
+    InvalidTokenAccountOwner,
+
+    #[msg("Fee vault already initialized")]
+    FeeVaultAlreadyInitialized,
+
+    #[msg("Pool fee vault account required")]
+    FeeVaultRequired,
+
+    #[msg("Memo program account required")]
+    MemoProgramRequired,
+
+    #[msg("Invalid exact amount")]
+    InvalidExactAmount,
+
+    #[msg("Escrow does not cover the exact amount plus fee")]
+    ExactAmountUnderfunded,
+
+    #[msg("Sender token account is required")]
+    SenderTokenAccountRequired,
+
+    #[msg("Invalid extension")]
+    InvalidExtension,
+
+    #[msg("Extension exceeds the pool's cap")]
+    ExtensionCapExceeded,
+
+    #[msg("Invalid recipient")]
+    InvalidRecipient,
+
+    #[msg("Timelock has not passed")]
+    TimelockActive,
+
+    #[msg("Operator nonce has already been used")]
+    NonceAlreadyUsed,
+
+    #[msg("Pool name or metadata URI is too long")]
+    PoolMetadataTooLong,
+
+    #[msg("Refund split must have 1-4 non-zero shares summing to 10000 bps in the pool mint")]
+    InvalidRefundSplit,
+
+    #[msg("Account is not a handshake account of the expected type")]
+    InvalidMigrationAccount,
+
+    #[msg("Account is already in the current layout")]
+    AccountAlreadyMigrated,
+
+    #[msg("Pool fee vault still holds fees")]
+    FeeVaultNotEmpty,
+
+    #[msg("Pool SOL fee exceeds the sender's maximum")]
+    SolFeeTooHigh,
 }
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface},
+};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::has_transfer_hook;
+use super::FeesWithdrawn;
+
+/// Sweep collected fees from several pools into one treasury (operator only).
+/// The treasury must be each pool's registered fee account, or the
+/// operator's ATA for pools without one, as in `withdraw_fees`.
+///
+/// Remaining accounts are `(pool, fee_source)` pairs, all writable, where the
+/// fee source is the pool's fee vault if it has one, else its token account.
+/// At most `MAX_BATCH_SIZE` pairs are accepted so an oversized batch fails
+/// with `BatchTooLarge` rather than running out of compute midway. Mints
+/// with transfer hooks must be withdrawn per pool with `withdraw_fees`.
+pub fn batch_withdraw_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchWithdrawFees<'info>>,
+) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    require!(
+        !remaining.is_empty() && remaining.len() % 2 == 0,
+        HandshakeError::InvalidRemainingAccounts
+    );
+    require!(
+        remaining.len() / 2 <= MAX_BATCH_SIZE,
+        HandshakeError::BatchTooLarge
+    );
+
+    // Remaining accounts carry the batch, leaving no room for a mint's
+    // transfer hook accounts
+    require!(
+        !has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+        HandshakeError::TransferHookNotAllowed
+    );
+
+    let operator = ctx.accounts.operator.key();
+    let mint = &ctx.accounts.mint;
+    let token_program = &ctx.accounts.token_program;
+
+    for pair in remaining.chunks(2) {
+        let pool_info = &pair[0];
+        let fee_source_info = &pair[1];
+        require!(
+            pool_info.is_writable && fee_source_info.is_writable,
+            HandshakeError::InvalidRemainingAccounts
+        );
+
+        let mut pool = Account::<Pool>::try_from(pool_info)?;
+
+        // Validate pool is the canonical PDA for its pool_id
+        let expected_pool = Pubkey::create_program_address(
+            &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]],
+            ctx.program_id,
+        )
+        .map_err(|_| HandshakeError::InvalidRemainingAccounts)?;
+        require_keys_eq!(
+            pool.key(),
+            expected_pool,
+            HandshakeError::InvalidRemainingAccounts
+        );
+
+        // Validate operator and mint
+        require!(pool.operator == operator, HandshakeError::Unauthorized);
+        require!(pool.mint == mint.key(), HandshakeError::InvalidMint);
+
+        // Validate the treasury is the pool's registered fee account, else the operator's ATA
+        let expected_treasury = if pool.operator_fee_account != Pubkey::default() {
+            pool.operator_fee_account
+        } else {
+            get_associated_token_address_with_program_id(
+                &pool.operator,
+                &pool.mint,
+                &token_program.key(),
+            )
+        };
+        require_keys_eq!(
+            ctx.accounts.treasury_token_account.key(),
+            expected_treasury,
+            HandshakeError::InvalidFeeAccount
+        );
+
+        // Validate the fee source is the pool's fee vault, else its ATA
+        let expected_fee_source = if pool.fee_vault != Pubkey::default() {
+            pool.fee_vault
+        } else {
+            get_associated_token_address_with_program_id(
+                &pool.key(),
+                &pool.mint,
+                &token_program.key(),
+            )
+        };
+        require_keys_eq!(
+            fee_source_info.key(),
+            expected_fee_source,
+            HandshakeError::InvalidRemainingAccounts
+        );
+
+        let fees = pool.collected_fees;
+        if fees == 0 {
+            continue;
+        }
+
+        // Transfer fees to treasury
+        let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+        let pool_signer_seeds = &[&pool_seeds[..]];
+
+        let transfer_accounts = TransferChecked {
+            from: fee_source_info.clone(),
+            mint: mint.to_account_info(),
+            to: ctx.accounts.treasury_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked(cpi_ctx, fees, mint.decimals)?;
+
+        // Reset collected fees and persist the pool
+        pool.reset_collected_fees();
+        pool.exit(ctx.program_id)?;
+
+        emit!(FeesWithdrawn {
+            pool: pool.key(),
+            operator,
+            amount: fees,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BatchWithdrawFees<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    /// The mint shared by every pool in the batch
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Treasury token account receiving all fees: the pools' registered fee
+    /// account, else the operator's ATA
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+
+/// Pay out a deferred refund to the sender's token account (permissionless)
+pub fn claim_refund<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ClaimRefund<'info>>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let pending_refund = &ctx.accounts.pending_refund;
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, pending_refund.amount)?;
+
+    // Transfer the owed amount to sender
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.pool_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.sender_token_account.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        pool_signer_seeds,
+    );
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        pending_refund.amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Update pool accounting
+    pool.add_withdrawal(pending_refund.amount)?;
+    pool.resolve_pending_refund()?;
+
+    emit!(RefundClaimed {
+        pool: pool.key(),
+        transfer: pending_refund.transfer,
+        sender: pending_refund.sender,
+        amount: pending_refund.amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    /// Anyone can call this (permissionless)
+    pub caller: Signer<'info>,
+
+    /// The pool holding the refund
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sender's token account to receive the refund
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pending_refund.sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Deferred refund to pay out (rent back to the operator who funded it)
+    #[account(
+        mut,
+        close = operator,
+        seeds = [
+            REFUND_SEED,
+            pending_refund.transfer.as_ref()
+        ],
+        bump = pending_refund.bump,
+        constraint = pending_refund.pool == pool.key() @ HandshakeError::InvalidPendingRefund
+    )]
+    pub pending_refund: Box<Account<'info, PendingRefund>>,
+
+    /// CHECK: Pool operator receives the pending refund rent on close.
+    #[account(
+        mut,
+        constraint = operator.key() == pool.operator @ HandshakeError::Unauthorized
+    )]
+    pub operator: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub pool: Pubkey,
+    pub transfer: Pubkey,
+    pub sender: Pubkey,
+    pub amount: u64,
+}
@@ -1,22 +1,43 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::memo::{build_memo, BuildMemo, Memo};
+use anchor_spl::token_interface::{burn, Burn, TransferChecked, Mint, TokenAccount, TokenInterface};
 use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+use super::{collect_sol_fee, route_fee_to_vault};
 
 /// Claim an active transfer as the recipient
 pub fn claim_transfer<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, ClaimTransfer<'info>>,
+    claim_code: Option<[u8; 32]>,
 ) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let transfer = &mut ctx.accounts.transfer;
 
     // Validate recipient can claim
     transfer.validate_recipient_can_claim(ctx.accounts.recipient.key())?;
+    transfer.validate_claim_code(&transfer.key(), claim_code)?;
+    transfer.validate_revealed()?;
+    transfer.validate_single_party_release()?;
+    transfer.validate_no_milestones()?;
 
-    // Calculate fee
-    let fee = pool.calculate_transfer_fee(transfer.amount);
-    let net_amount = transfer.amount
-        .checked_sub(fee)
-        .ok_or(HandshakeError::CalculationError)?;
+    // Defense in depth: the account constraints already pin these owners
+    require!(
+        ctx.accounts.pool_token_account.owner == pool.key(),
+        HandshakeError::InvalidTokenAccountOwner
+    );
+    require!(
+        ctx.accounts.recipient_token_account.owner == transfer.recipient,
+        HandshakeError::InvalidTokenAccountOwner
+    );
+
+    // Calculate fee and any change owed back to the sender
+    let (fee, net_amount, change) = pool.calculate_payout(transfer)?;
+
+    // Stale transfers can only be force-resolved
+    transfer.validate_not_stale()?;
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
 
     // Transfer net amount to recipient using pool authority
     let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
@@ -33,14 +54,85 @@ pub fn claim_transfer<'a, 'b, 'c, 'info>(
         transfer_accounts,
         pool_signer_seeds,
     );
-    transfer_checked(cpi_ctx, net_amount, ctx.accounts.mint.decimals)?;
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        net_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Attach the transfer memo to the payout, if the pool asks for it
+    notify_memo(pool, transfer, ctx.accounts.memo_program.as_ref())?;
+
+    // Return the excess of an exact-amount transfer to the sender
+    if change > 0 {
+        let sender_token_account = ctx
+            .accounts
+            .sender_token_account
+            .as_ref()
+            .ok_or(HandshakeError::SenderTokenAccountRequired)?;
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: sender_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            change,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    // Burn the configured share of the fee, keep the rest as collected fees
+    let fee_burned = pool.calculate_fee_burn(fee);
+    if fee_burned > 0 {
+        let burn_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let burn_cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            burn_accounts,
+            pool_signer_seeds,
+        );
+        burn(burn_cpi_ctx, fee_burned)?;
+    }
+    let fee_collected = fee
+        .checked_sub(fee_burned)
+        .ok_or(HandshakeError::CalculationError)?;
+
+    // Move the collected share into the fee vault, if the pool has one
+    route_fee_to_vault(
+        pool,
+        &ctx.accounts.pool_token_account,
+        ctx.accounts.fee_vault.as_deref(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        fee_collected,
+    )?;
 
     // Update pool accounting
     pool.add_withdrawal(transfer.amount)?;
-    if fee > 0 {
-        pool.add_collected_fees(fee)?;
+    collect_sol_fee(pool, transfer)?;
+    if fee_collected > 0 {
+        pool.add_collected_fees(fee_collected)?;
+        emit!(FeeAccrued {
+            pool: pool.key(),
+            transfer: transfer.key(),
+            amount: fee_collected,
+            outcome: TransferStatus::Claimed,
+        });
     }
-    pool.increment_transfers_resolved()?;
+    pool.increment_transfers_resolved(transfer.created_at)?;
 
     // Mark transfer as claimed and close (rent to sender)
     transfer.mark_as_claimed()?;
@@ -52,12 +144,43 @@ pub fn claim_transfer<'a, 'b, 'c, 'info>(
         recipient: transfer.recipient,
         amount: transfer.amount,
         fee,
+        fee_burned,
         net_amount,
+        change,
+        transfer_fee_bps: pool.effective_fee_bps(transfer),
+        fee_burn_bps: pool.fee_burn_bps,
     });
 
     Ok(())
 }
 
+/// Attach the transfer memo to a recipient payout with an SPL Memo CPI when
+/// the pool has notify_memo set. Transfers without a memo get no memo.
+pub(crate) fn notify_memo<'info>(
+    pool: &Pool,
+    transfer: &SecureTransfer,
+    memo_program: Option<&Program<'info, Memo>>,
+) -> Result<()> {
+    if !pool.notify_memo {
+        return Ok(());
+    }
+    let memo_program = memo_program.ok_or(HandshakeError::MemoProgramRequired)?;
+
+    let memo_len = transfer
+        .memo
+        .iter()
+        .position(|b| *b == 0)
+        .unwrap_or(transfer.memo.len());
+    if memo_len == 0 {
+        return Ok(());
+    }
+
+    build_memo(
+        CpiContext::new(memo_program.to_account_info(), BuildMemo {}),
+        &transfer.memo[..memo_len],
+    )
+}
+
 #[derive(Accounts)]
 pub struct ClaimTransfer<'info> {
     #[account(mut)]
@@ -74,8 +197,9 @@ pub struct ClaimTransfer<'info> {
     )]
     pub pool: Box<Account<'info, Pool>>,
 
-    /// The mint for validation
+    /// The mint for validation (mutable for fee burns)
     #[account(
+        mut,
         constraint = mint.key() == pool.mint
     )]
     pub mint: InterfaceAccount<'info, Mint>,
@@ -98,6 +222,15 @@ pub struct ClaimTransfer<'info> {
     )]
     pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Sender's token account to receive change (required when an exact-amount transfer has change)
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     /// Transfer account to claim (closed to sender on success)
     #[account(
         mut,
@@ -113,6 +246,13 @@ pub struct ClaimTransfer<'info> {
     )]
     pub sender: AccountInfo<'info>,
 
+    /// Pool's fee vault (required when the pool has one)
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// SPL Memo program (required when the pool has notify_memo set)
+    pub memo_program: Option<Program<'info, Memo>>,
+
     pub token_program: Interface<'info, TokenInterface>,
 }
 
@@ -124,5 +264,21 @@ pub struct TransferClaimed {
     pub recipient: Pubkey,
     pub amount: u64,
     pub fee: u64,
+    pub fee_burned: u64,
     pub net_amount: u64,
+    /// Excess returned to the sender; `net_amount + fee + change == amount`
+    pub change: u64,
+    /// Fee config applied at resolution
+    pub transfer_fee_bps: u16,
+    pub fee_burn_bps: u16,
+}
+
+/// Emitted whenever fee revenue is added to a pool's collected fees
+#[event]
+pub struct FeeAccrued {
+    pub pool: Pubkey,
+    pub transfer: Pubkey,
+    pub amount: u64,
+    /// Resolution that produced the fee
+    pub outcome: TransferStatus,
 }
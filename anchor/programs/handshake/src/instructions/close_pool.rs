@@ -1,11 +1,17 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{
-    close_account, transfer_checked, CloseAccount, TransferChecked, Mint, TokenAccount, TokenInterface,
+    close_account, CloseAccount, TransferChecked, Mint, TokenAccount, TokenInterface,
 };
 use crate::{state::*, errors::*, constants::*};
-
-/// Close the pool (operator only, requires no outstanding transfers)
-pub fn close_pool(ctx: Context<ClosePool>, withdrawal_amount: u64) -> Result<()> {
+use crate::transfer_hook::transfer_checked_with_hooks;
+
+/// Close the pool (operator only, requires no outstanding transfers).
+/// Fees left in the fee vault are swept to the operator, and the fee vault,
+/// stake vault and pool token account are closed along with the pool.
+pub fn close_pool<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ClosePool<'info>>,
+    withdrawal_amount: u64,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
 
     // Validate operator
@@ -20,6 +26,9 @@ pub fn close_pool(ctx: Context<ClosePool>, withdrawal_amount: u64) -> Result<()>
         HandshakeError::OutstandingTransfers
     );
 
+    // Validate the operator's stake has been withdrawn
+    require!(pool.operator_stake == 0, HandshakeError::OperatorStakeOutstanding);
+
     // Validate withdrawal amount matches pool balance
     require!(
         withdrawal_amount <= ctx.accounts.pool_token_account.amount,
@@ -42,7 +51,76 @@ pub fn close_pool(ctx: Context<ClosePool>, withdrawal_amount: u64) -> Result<()>
             transfer_accounts,
             pool_signer_seeds,
         );
-        transfer_checked(cpi_ctx, withdrawal_amount, ctx.accounts.mint.decimals)?;
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            withdrawal_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    // Sweep any fees left in the fee vault to the operator, then close it
+    let fees_swept = if pool.fee_vault != Pubkey::default() {
+        let fee_vault = ctx
+            .accounts
+            .fee_vault
+            .as_deref()
+            .ok_or(HandshakeError::FeeVaultRequired)?;
+        require_keys_eq!(fee_vault.key(), pool.fee_vault, HandshakeError::FeeVaultRequired);
+
+        let fees_swept = fee_vault.amount;
+        if fees_swept > 0 {
+            let transfer_accounts = TransferChecked {
+                from: fee_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.operator_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                pool_signer_seeds,
+            );
+            transfer_checked_with_hooks(
+                cpi_ctx,
+                ctx.remaining_accounts,
+                fees_swept,
+                ctx.accounts.mint.decimals,
+            )?;
+        }
+
+        let close_accounts = CloseAccount {
+            account: fee_vault.to_account_info(),
+            destination: ctx.accounts.operator.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_accounts,
+            pool_signer_seeds,
+        ))?;
+        fees_swept
+    } else {
+        0
+    };
+
+    // Close the stake vault, if one was created (the stake is already withdrawn)
+    if pool.stake_vault_bump != 0 {
+        let stake_vault = ctx
+            .accounts
+            .stake_vault
+            .as_deref()
+            .ok_or(HandshakeError::OperatorStakeOutstanding)?;
+        let close_accounts = CloseAccount {
+            account: stake_vault.to_account_info(),
+            destination: ctx.accounts.operator.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_accounts,
+            pool_signer_seeds,
+        ))?;
     }
 
     // Close the pool token account (reclaim rent to operator)
@@ -62,6 +140,7 @@ pub fn close_pool(ctx: Context<ClosePool>, withdrawal_amount: u64) -> Result<()>
         pool: pool.key(),
         operator: pool.operator,
         withdrawal_amount,
+        fees_swept,
     });
 
     Ok(())
@@ -108,6 +187,21 @@ pub struct ClosePool<'info> {
     )]
     pub operator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Pool's fee vault (required when the pool has one)
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Pool's stake vault (required once the operator has staked)
+    #[account(
+        mut,
+        seeds = [
+            STAKE_SEED,
+            pool.key().as_ref()
+        ],
+        bump = pool.stake_vault_bump
+    )]
+    pub stake_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
 }
 
@@ -116,4 +210,6 @@ pub struct PoolClosed {
     pub pool: Pubkey,
     pub operator: Pubkey,
     pub withdrawal_amount: u64,
+    /// Fees left in the fee vault and paid to the operator
+    pub fees_swept: u64,
 }
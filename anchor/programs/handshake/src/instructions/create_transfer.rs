@@ -1,11 +1,16 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface},
+    token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface},
 };
 use crate::{state::*, errors::*, constants::*};
+use solana_sdk_ids::sysvar::instructions as instructions_sysvar;
+use crate::signature::{coupon_message, verify_ed25519_instruction};
+use crate::transfer_hook::{has_transfer_hook, transfer_checked_with_hooks};
 
 /// Create a new transfer (escrow)
+#[allow(clippy::too_many_arguments)]
 pub fn create_transfer<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, CreateTransfer<'info>>,
     recipient: Pubkey,
@@ -14,6 +19,13 @@ pub fn create_transfer<'a, 'b, 'c, 'info>(
     memo: String,
     claimable_after: i64,
     claimable_until: i64,
+    claim_code_hash: Option<[u8; 32]>,
+    keeper_tip: u64,
+    refund_token_account: Option<Pubkey>,
+    requires_mutual: bool,
+    coupon: Option<FeeCoupon>,
+    exact_amount: Option<u64>,
+    max_sol_fee_lamports: u64,
 ) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let transfer = &mut ctx.accounts.transfer;
@@ -21,9 +33,100 @@ pub fn create_transfer<'a, 'b, 'c, 'info>(
     // Validate pool is not paused
     require!(!pool.is_paused, HandshakeError::PoolPaused);
 
+    // Validate the mint's transfer hook, if any, is allowed by the pool
+    require!(
+        pool.allow_transfer_hooks || !has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+        HandshakeError::TransferHookNotAllowed
+    );
+
     // Validate amount
     require!(amount > 0, HandshakeError::DepositTooSmall);
 
+    // Sending to yourself is almost always a mistake
+    require!(
+        pool.allow_self_transfer || recipient != ctx.accounts.sender.key(),
+        HandshakeError::SelfTransfer
+    );
+
+    // Keeper tip is carved out of the amount on expiry, so it must leave a refund
+    require!(keeper_tip < amount, HandshakeError::InvalidKeeperTip);
+
+    // A refund override must be a live token account in the pool mint, or
+    // rejects would fail or strand the refund
+    if let Some(refund_token_account) = refund_token_account {
+        let account = ctx
+            .accounts
+            .refund_token_account
+            .as_deref()
+            .ok_or(HandshakeError::InvalidRefundAccount)?;
+        require!(
+            account.key() == refund_token_account
+                && account.mint == pool.mint
+                && account.owner != Pubkey::default()
+                && !account.is_frozen()
+                && account.to_account_info().owner == ctx.accounts.token_program.key,
+            HandshakeError::InvalidRefundAccount
+        );
+    }
+
+    // Validate amount meets the recipient's expectation, if one is registered
+    RecipientExpectation::validate_amount(
+        &ctx.accounts.recipient_expectation.to_account_info(),
+        ctx.program_id,
+        amount,
+    )?;
+
+    // Redeem a fee coupon signed by the pool's coupon signer, if one is attached
+    let mut fee_discount_bps = 0;
+    match coupon {
+        Some(coupon) => {
+            require!(
+                pool.coupon_signer != Pubkey::default() && coupon.discount_bps <= 10000,
+                HandshakeError::InvalidCoupon
+            );
+            require!(
+                coupon.expiry >= Clock::get()?.unix_timestamp,
+                HandshakeError::CouponExpired
+            );
+            let instructions_sysvar = ctx
+                .accounts
+                .instructions_sysvar
+                .as_ref()
+                .ok_or(HandshakeError::InvalidCoupon)?;
+            let message = coupon_message(
+                &pool.key(),
+                &ctx.accounts.sender.key(),
+                coupon.discount_bps,
+                coupon.expiry,
+            );
+            verify_ed25519_instruction(
+                &instructions_sysvar.to_account_info(),
+                &pool.coupon_signer,
+                &message,
+            )?;
+
+            // The redemption PDA can only be created once per sender (replay protection)
+            let redemption = ctx
+                .accounts
+                .coupon_redemption
+                .as_mut()
+                .ok_or(HandshakeError::InvalidCoupon)?;
+            redemption.version = 1;
+            redemption.bump = ctx.bumps.coupon_redemption.ok_or(HandshakeError::InvalidCoupon)?;
+            redemption.pool = pool.key();
+            redemption.sender = ctx.accounts.sender.key();
+            redemption.transfer = transfer.key();
+            redemption.discount_bps = coupon.discount_bps;
+            redemption.redeemed_at = Clock::get()?.unix_timestamp;
+
+            fee_discount_bps = coupon.discount_bps;
+        }
+        None => require!(
+            ctx.accounts.coupon_redemption.is_none(),
+            HandshakeError::InvalidCoupon
+        ),
+    }
+
     // Transfer tokens from sender to pool
     let transfer_accounts = TransferChecked {
         from: ctx.accounts.sender_token_account.to_account_info(),
@@ -35,7 +138,12 @@ pub fn create_transfer<'a, 'b, 'c, 'info>(
         ctx.accounts.token_program.to_account_info(),
         transfer_accounts,
     );
-    transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
 
     // Initialize transfer account
     transfer.initialize(
@@ -49,6 +157,37 @@ pub fn create_transfer<'a, 'b, 'c, 'info>(
         claimable_after,
         claimable_until,
     )?;
+    transfer.claim_code_hash = claim_code_hash;
+    transfer.keeper_tip = keeper_tip;
+    transfer.refund_token_account = refund_token_account;
+    transfer.requires_mutual = requires_mutual;
+    transfer.fee_discount_bps = fee_discount_bps;
+    transfer.exact_amount = exact_amount;
+    transfer.lock_stale_terms(pool.max_lifetime_seconds, pool.stale_fee_bps)?;
+    charge_sol_fee(
+        pool,
+        transfer,
+        &ctx.accounts.sender,
+        &ctx.accounts.system_program,
+        max_sol_fee_lamports,
+    )?;
+
+    // A sender within the pool's free-transfer allowance pays no fee. The waiver
+    // is decided here so it holds on whichever path releases the transfer; a
+    // transfer paying its fee in SOL doesn't use up the allowance.
+    if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+        if !pool.fee_in_sol {
+            transfer.fee_waived = sender_profile.record_transfer(pool.free_transfer_count)?;
+        }
+    }
+
+    // An exact-amount transfer must cover the payout plus its fee, at a rate
+    // locked now so the check still holds when it is accepted
+    if let Some(exact_amount) = exact_amount {
+        require!(exact_amount > 0, HandshakeError::InvalidExactAmount);
+        transfer.locked_fee_bps = Some(pool.transfer_fee_bps);
+        pool.calculate_payout(transfer)?;
+    }
 
     // Update pool accounting
     pool.add_deposit(amount)?;
@@ -61,14 +200,79 @@ pub fn create_transfer<'a, 'b, 'c, 'info>(
         recipient: transfer.recipient,
         amount,
         nonce,
-        memo,
+        memo: if pool.full_events() { memo } else { String::new() },
         claimable_after,
         claimable_until,
+        claim_code_hash,
+        keeper_tip,
+        refund_token_account,
+        requires_mutual,
+        fee_discount_bps,
+        exact_amount,
     });
 
     Ok(())
 }
 
+/// Escrow the pool's SOL fee from the sender in the transfer account if the
+/// pool takes fees in SOL, refusing a fee above `max_sol_fee_lamports`. The
+/// pool collects it when the transfer is released (`collect_sol_fee`); if the
+/// transfer is refunded instead, it goes back to the sender with the rent.
+pub(crate) fn charge_sol_fee<'info>(
+    pool: &Account<'info, Pool>,
+    transfer: &mut Account<'info, SecureTransfer>,
+    sender: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    max_sol_fee_lamports: u64,
+) -> Result<()> {
+    if !pool.fee_in_sol {
+        return Ok(());
+    }
+
+    let lamports = pool.sol_fee_lamports;
+    require!(lamports <= max_sol_fee_lamports, HandshakeError::SolFeeTooHigh);
+    if lamports > 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: sender.to_account_info(),
+                to: transfer.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, lamports)?;
+    }
+
+    transfer.fee_paid_in_sol = true;
+    transfer.sol_fee_lamports = lamports;
+    Ok(())
+}
+
+/// Move a released transfer's escrowed SOL fee into the pool's collected SOL fees
+pub(crate) fn collect_sol_fee<'info>(
+    pool: &mut Account<'info, Pool>,
+    transfer: &mut Account<'info, SecureTransfer>,
+) -> Result<()> {
+    let lamports = transfer.sol_fee_lamports;
+    if lamports == 0 {
+        return Ok(());
+    }
+
+    transfer.sub_lamports(lamports)?;
+    pool.add_lamports(lamports)?;
+    transfer.sol_fee_lamports = 0;
+    pool.collected_sol_fees = pool
+        .collected_sol_fees
+        .checked_add(lamports)
+        .ok_or(HandshakeError::MathOverflow)?;
+
+    emit!(SolFeeCollected {
+        pool: pool.key(),
+        sender: transfer.sender,
+        lamports,
+    });
+    Ok(())
+}
+
 #[derive(Accounts)]
 #[instruction(recipient: Pubkey, nonce: u64)]
 pub struct CreateTransfer<'info> {
@@ -127,6 +331,50 @@ pub struct CreateTransfer<'info> {
     )]
     pub transfer: Box<Account<'info, SecureTransfer>>,
 
+    /// CHECK: Recipient's expectation PDA; may be uninitialized, deserialized in handler.
+    #[account(
+        seeds = [
+            EXPECTATION_SEED,
+            pool.key().as_ref(),
+            recipient.as_ref()
+        ],
+        bump
+    )]
+    pub recipient_expectation: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, used to read a coupon's Ed25519 precompile instruction.
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// Coupon redemption record, created only when a fee coupon is attached
+    #[account(
+        init,
+        payer = sender,
+        space = CouponRedemption::SPACE,
+        seeds = [
+            COUPON_SEED,
+            pool.key().as_ref(),
+            sender.key().as_ref()
+        ],
+        bump
+    )]
+    pub coupon_redemption: Option<Box<Account<'info, CouponRedemption>>>,
+
+    /// Refund override account, required when `refund_token_account` is set
+    pub refund_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Sender's profile in this pool, counting towards its free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            sender.key().as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -143,4 +391,27 @@ pub struct TransferCreated {
     pub memo: String,
     pub claimable_after: i64,
     pub claimable_until: i64,
+    pub claim_code_hash: Option<[u8; 32]>,
+    pub keeper_tip: u64,
+    pub refund_token_account: Option<Pubkey>,
+    pub requires_mutual: bool,
+    pub fee_discount_bps: u16,
+    /// Amount the recipient receives; the excess is returned as change
+    pub exact_amount: Option<u64>,
+}
+
+/// Fee coupon signed off-chain by the pool's coupon signer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeeCoupon {
+    /// Discount off the pool fee (bps)
+    pub discount_bps: u16,
+    /// Last unix timestamp the coupon can be redeemed
+    pub expiry: i64,
+}
+
+#[event]
+pub struct SolFeeCollected {
+    pub pool: Pubkey,
+    pub sender: Pubkey,
+    pub lamports: u64,
 }
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TransferChecked};
+use crate::{state::*, errors::*};
+use crate::transfer_hook::{has_transfer_hook, transfer_checked_with_hooks};
+use super::{charge_sol_fee, CreateTransfer};
+
+/// Create a sealed transfer. Escrows `max_amount` and stores a commitment to
+/// the real amount, which is disclosed later via `reveal_transfer`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_transfer_committed<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CreateTransfer<'info>>,
+    recipient: Pubkey,
+    nonce: u64,
+    max_amount: u64,
+    amount_commitment: [u8; 32],
+    memo: String,
+    claimable_after: i64,
+    claimable_until: i64,
+    max_sol_fee_lamports: u64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate pool is not paused
+    require!(!pool.is_paused, HandshakeError::PoolPaused);
+
+    // Validate the mint's transfer hook, if any, is allowed by the pool
+    require!(
+        pool.allow_transfer_hooks || !has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+        HandshakeError::TransferHookNotAllowed
+    );
+
+    // Validate amount
+    require!(max_amount > 0, HandshakeError::DepositTooSmall);
+
+    // Sending to yourself is almost always a mistake
+    require!(
+        pool.allow_self_transfer || recipient != ctx.accounts.sender.key(),
+        HandshakeError::SelfTransfer
+    );
+
+    // The revealed amount is checked again on reveal; fail early if even the cap is too low
+    RecipientExpectation::validate_amount(
+        &ctx.accounts.recipient_expectation.to_account_info(),
+        ctx.program_id,
+        max_amount,
+    )?;
+
+    // Fee coupons are not supported on sealed transfers
+    require!(
+        ctx.accounts.coupon_redemption.is_none(),
+        HandshakeError::InvalidCoupon
+    );
+
+    // Transfer the declared maximum from sender to pool
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.sender_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.pool_token_account.to_account_info(),
+        authority: ctx.accounts.sender.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+    );
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        max_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Initialize transfer account
+    transfer.initialize(
+        ctx.bumps.transfer,
+        nonce,
+        ctx.accounts.sender.key(),
+        recipient,
+        pool.key(),
+        max_amount,
+        memo.clone(),
+        claimable_after,
+        claimable_until,
+    )?;
+    transfer.amount_commitment = Some(amount_commitment);
+    transfer.lock_stale_terms(pool.max_lifetime_seconds, pool.stale_fee_bps)?;
+    charge_sol_fee(
+        pool,
+        transfer,
+        &ctx.accounts.sender,
+        &ctx.accounts.system_program,
+        max_sol_fee_lamports,
+    )?;
+
+    // A sender within the pool's free-transfer allowance pays no fee. The waiver
+    // is decided here so it holds on whichever path releases the transfer; a
+    // transfer paying its fee in SOL doesn't use up the allowance.
+    if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+        if !pool.fee_in_sol {
+            transfer.fee_waived = sender_profile.record_transfer(pool.free_transfer_count)?;
+        }
+    }
+
+    // Update pool accounting
+    pool.add_deposit(max_amount)?;
+    pool.increment_transfers_created()?;
+
+    emit!(TransferCommitted {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        sender: transfer.sender,
+        recipient: transfer.recipient,
+        max_amount,
+        amount_commitment,
+        nonce,
+        memo: if pool.full_events() { memo } else { String::new() },
+        claimable_after,
+        claimable_until,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TransferCommitted {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub max_amount: u64,
+    pub amount_commitment: [u8; 32],
+    pub nonce: u64,
+    pub memo: String,
+    pub claimable_after: i64,
+    pub claimable_until: i64,
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
 use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
 
 /// Decline a transfer as the recipient (full refund to sender, no fee)
 pub fn decline_transfer<'a, 'b, 'c, 'info>(
@@ -19,6 +20,12 @@ pub fn decline_transfer<'a, 'b, 'c, 'info>(
     // Validate transfer is active
     transfer.validate_active()?;
 
+    // Stale transfers can only be force-resolved
+    transfer.validate_not_stale()?;
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
     // Transfer full amount back to sender (no fee on decline)
     let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
     let pool_signer_seeds = &[&pool_seeds[..]];
@@ -34,11 +41,21 @@ pub fn decline_transfer<'a, 'b, 'c, 'info>(
         transfer_accounts,
         pool_signer_seeds,
     );
-    transfer_checked(cpi_ctx, transfer.amount, ctx.accounts.mint.decimals)?;
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        transfer.amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Give a waived transfer's free-transfer allowance back
+    if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+        sender_profile.release_transfer(transfer);
+    }
 
     // Update pool accounting
     pool.add_withdrawal(transfer.amount)?;
-    pool.increment_transfers_resolved()?;
+    pool.increment_transfers_resolved(transfer.created_at)?;
 
     // Mark transfer as declined
     transfer.mark_as_declined()?;
@@ -110,6 +127,18 @@ pub struct DeclineTransfer<'info> {
     )]
     pub sender: AccountInfo<'info>,
 
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            transfer.sender.as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
 }
 
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+
+/// Deposit operator stake into the pool's stake vault (operator only). The
+/// vault is created once with `init_stake_vault`.
+pub fn deposit_operator_stake<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, DepositOperatorStake<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    require!(amount > 0, HandshakeError::DepositTooSmall);
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.operator_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.operator.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+    );
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    pool.operator_stake = pool
+        .operator_stake
+        .checked_add(amount)
+        .ok_or(HandshakeError::MathOverflow)?;
+
+    emit!(OperatorStakeChanged {
+        pool: pool.key(),
+        operator_stake: pool.operator_stake,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositOperatorStake<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Operator's token account funding the stake
+    #[account(
+        mut,
+        token::mint = pool.mint,
+        token::authority = operator,
+        token::token_program = token_program
+    )]
+    pub operator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Stake vault, verified against the bump cached by `init_stake_vault`
+    #[account(
+        mut,
+        seeds = [
+            STAKE_SEED,
+            pool.key().as_ref()
+        ],
+        bump = pool.stake_vault_bump
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct OperatorStakeChanged {
+    pub pool: Pubkey,
+    /// Stake held after the change
+    pub operator_stake: u64,
+}
@@ -1,10 +1,13 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
 use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
 
 /// Emergency: destroy a transfer (operator only, pool must be paused)
 /// Returns escrowed funds to the original sender.
-pub fn destroy_transfer(ctx: Context<DestroyTransfer>) -> Result<()> {
+pub fn destroy_transfer<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, DestroyTransfer<'info>>,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let transfer = &mut ctx.accounts.transfer;
 
@@ -20,6 +23,9 @@ pub fn destroy_transfer(ctx: Context<DestroyTransfer>) -> Result<()> {
     // Validate transfer is active
     transfer.validate_active()?;
 
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
     // Return escrowed amount to the original sender (NOT the operator)
     let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
     let pool_signer_seeds = &[&pool_seeds[..]];
@@ -35,11 +41,21 @@ pub fn destroy_transfer(ctx: Context<DestroyTransfer>) -> Result<()> {
         transfer_accounts,
         pool_signer_seeds,
     );
-    transfer_checked(cpi_ctx, transfer.amount, ctx.accounts.mint.decimals)?;
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        transfer.amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Give a waived transfer's free-transfer allowance back
+    if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+        sender_profile.release_transfer(transfer);
+    }
 
     // Update pool accounting
     pool.add_withdrawal(transfer.amount)?;
-    pool.increment_transfers_resolved()?;
+    pool.increment_transfers_resolved(transfer.created_at)?;
 
     // Mark as cancelled (closed to sender)
     transfer.mark_as_cancelled()?;
@@ -110,6 +126,18 @@ pub struct DestroyTransfer<'info> {
     )]
     pub transfer: Box<Account<'info, SecureTransfer>>,
 
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            transfer.sender.as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
 }
 
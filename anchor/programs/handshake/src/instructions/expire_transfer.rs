@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{burn, Burn, TransferChecked, Mint, TokenAccount, TokenInterface};
 use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+use super::{FeeAccrued, collect_sol_fee, route_fee_to_vault};
 
-/// Expire a transfer past its claimable_until deadline (permissionless)
+/// Expire a transfer past its claimable_until deadline (permissionless).
+/// If the caller supplies a token account, the transfer's keeper tip is paid
+/// to it out of the escrowed amount. The rest is refunded to the sender, or,
+/// if the pool forwards expired transfers, paid to the recipient minus fee.
+/// Sealed, claim-code, mutual-accept, milestone and exact-amount transfers are
+/// always refunded.
 pub fn expire_transfer<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, ExpireTransfer<'info>>,
 ) -> Result<()> {
@@ -21,26 +28,157 @@ pub fn expire_transfer<'a, 'b, 'c, 'info>(
     let is_expired = transfer.is_expired()?;
     require!(is_expired, HandshakeError::CannotClaim);
 
-    // Transfer full amount back to sender (NO fee on expiry)
+    // Stale transfers can only be force-resolved
+    transfer.validate_not_stale()?;
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
     let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
     let pool_signer_seeds = &[&pool_seeds[..]];
 
-    let transfer_accounts = TransferChecked {
-        from: ctx.accounts.pool_token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
-        to: ctx.accounts.sender_token_account.to_account_info(),
-        authority: pool.to_account_info(),
+    // Pay the keeper tip to the caller, if they provided an account for it.
+    // Milestone releases and reveals can shrink the escrow below the tip set
+    // at creation, so the tip is capped at what is left.
+    let tip_due = transfer.keeper_tip.min(transfer.amount);
+    let keeper_tip = match &ctx.accounts.caller_token_account {
+        Some(caller_token_account) if tip_due > 0 => {
+            let transfer_accounts = TransferChecked {
+                from: ctx.accounts.pool_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: caller_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                pool_signer_seeds,
+            );
+            transfer_checked_with_hooks(
+                cpi_ctx,
+                ctx.remaining_accounts,
+                tip_due,
+                ctx.accounts.mint.decimals,
+            )?;
+            tip_due
+        }
+        _ => 0,
     };
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_accounts,
-        pool_signer_seeds,
-    );
-    transfer_checked(cpi_ctx, transfer.amount, ctx.accounts.mint.decimals)?;
+
+    let remaining = transfer
+        .amount
+        .checked_sub(keeper_tip)
+        .ok_or(HandshakeError::MathOverflow)?;
+
+    // Forwarding would bypass a claim code, an unrevealed amount, mutual consent,
+    // outstanding milestones or the change owed on an exact-amount transfer
+    let forward = pool.expiry_behavior == EXPIRY_FORWARD_RECIPIENT
+        && transfer.claim_code_hash.is_none()
+        && transfer.amount_commitment.is_none()
+        && !transfer.requires_mutual
+        && transfer.milestones.is_empty()
+        && transfer.exact_amount.is_none();
+
+    let mut fee = 0;
+    if forward {
+        // Pay the rest to the recipient minus fee
+        let recipient_token_account = ctx
+            .accounts
+            .recipient_token_account
+            .as_ref()
+            .ok_or(HandshakeError::RecipientTokenAccountRequired)?;
+
+        fee = pool.calculate_fee_for(transfer, remaining);
+        let net_amount = remaining
+            .checked_sub(fee)
+            .ok_or(HandshakeError::CalculationError)?;
+
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: recipient_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            net_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        // Burn the configured share of the fee, keep the rest as collected fees
+        let fee_burned = pool.calculate_fee_burn(fee);
+        if fee_burned > 0 {
+            let burn_accounts = Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.pool_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            let burn_cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                burn_accounts,
+                pool_signer_seeds,
+            );
+            burn(burn_cpi_ctx, fee_burned)?;
+        }
+        let fee_collected = fee
+            .checked_sub(fee_burned)
+            .ok_or(HandshakeError::CalculationError)?;
+
+        // Move the collected share into the fee vault, if the pool has one
+        route_fee_to_vault(
+            pool,
+            &ctx.accounts.pool_token_account,
+            ctx.accounts.fee_vault.as_deref(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            fee_collected,
+        )?;
+        collect_sol_fee(pool, transfer)?;
+        if fee_collected > 0 {
+            pool.add_collected_fees(fee_collected)?;
+            emit!(FeeAccrued {
+                pool: pool.key(),
+                transfer: transfer.key(),
+                amount: fee_collected,
+                outcome: TransferStatus::Expired,
+            });
+        }
+    } else {
+        // Transfer the rest back to sender (NO fee on refund)
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.sender_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            remaining,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        // Give a waived transfer's free-transfer allowance back
+        if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+            sender_profile.release_transfer(transfer);
+        }
+    }
 
     // Update pool accounting
     pool.add_withdrawal(transfer.amount)?;
-    pool.increment_transfers_resolved()?;
+    pool.increment_transfers_resolved(transfer.created_at)?;
 
     // Mark transfer as expired
     transfer.mark_as_expired()?;
@@ -51,6 +189,9 @@ pub fn expire_transfer<'a, 'b, 'c, 'info>(
         sender: transfer.sender,
         recipient: transfer.recipient,
         amount: transfer.amount,
+        keeper_tip,
+        forwarded: forward,
+        fee,
     });
 
     Ok(())
@@ -72,8 +213,9 @@ pub struct ExpireTransfer<'info> {
     )]
     pub pool: Box<Account<'info, Pool>>,
 
-    /// The mint for validation
+    /// The mint for validation (mutable for fee burns when forwarding)
     #[account(
+        mut,
         constraint = mint.key() == pool.mint
     )]
     pub mint: InterfaceAccount<'info, Mint>,
@@ -104,6 +246,24 @@ pub struct ExpireTransfer<'info> {
     )]
     pub transfer: Box<Account<'info, SecureTransfer>>,
 
+    /// Recipient's token account, required when the pool forwards expired transfers
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Caller's token account to receive the keeper tip (optional)
+    #[account(
+        mut,
+        token::mint = pool.mint,
+        token::authority = caller,
+        token::token_program = token_program
+    )]
+    pub caller_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     /// CHECK: Sender receives rent refund on close.
     #[account(
         mut,
@@ -111,6 +271,22 @@ pub struct ExpireTransfer<'info> {
     )]
     pub sender: AccountInfo<'info>,
 
+    /// Pool's fee vault (required when the pool has one)
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            transfer.sender.as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
 }
 
@@ -121,4 +297,10 @@ pub struct TransferExpired {
     pub sender: Pubkey,
     pub recipient: Pubkey,
     pub amount: u64,
+    /// Keeper tip paid to the caller out of `amount`
+    pub keeper_tip: u64,
+    /// Whether the rest went to the recipient (true) or back to the sender
+    pub forwarded: bool,
+    /// Fee charged when forwarded (refunds are not charged)
+    pub fee: u64,
 }
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Extend a transfer's claim deadline by mutual agreement (sender and
+/// recipient both sign). The total extension is capped by the pool's
+/// `max_extension_seconds`, and an expired deadline cannot be revived.
+pub fn extend_transfer(ctx: Context<ExtendTransfer>, extension_seconds: i64) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    let clock = Clock::get()?;
+    let new_deadline = transfer.extend_deadline(
+        extension_seconds,
+        pool.max_extension_seconds,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(TransferExtended {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        new_deadline,
+        extended_by: transfer.extended_by,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExtendTransfer<'info> {
+    pub sender: Signer<'info>,
+
+    pub recipient: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Transfer whose deadline is extended
+    #[account(
+        mut,
+        constraint = transfer.pool == pool.key(),
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized,
+        constraint = transfer.recipient == recipient.key() @ HandshakeError::Unauthorized
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+}
+
+#[event]
+pub struct TransferExtended {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    /// New `claimable_until`
+    pub new_deadline: i64,
+    /// Total extension applied so far
+    pub extended_by: i64,
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+use super::{refund_destination, TransferRejected};
+
+/// Finalize a soft reject once its undo window has passed (permissionless).
+/// Refunds the full amount to the sender's refund account or ATA, which must
+/// exist; soft-rejected refunds are never deferred.
+pub fn finalize_reject<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, FinalizeReject<'info>>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate the undo window has passed
+    let clock = Clock::get()?;
+    transfer.finalize_reject_pending(clock.unix_timestamp)?;
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
+    let refund_destination = refund_destination(
+        transfer,
+        &pool.mint,
+        ctx.accounts
+            .sender_token_account
+            .as_deref()
+            .map(|account| account.to_account_info()),
+        ctx.accounts.refund_token_account.as_deref(),
+    )?
+    .ok_or(HandshakeError::InvalidRefundAccount)?;
+
+    // Transfer full amount back to sender (no fee on rejection)
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.pool_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: refund_destination,
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        pool_signer_seeds,
+    );
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        transfer.amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Give a waived transfer's free-transfer allowance back
+    if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+        sender_profile.release_transfer(transfer);
+    }
+
+    // Update pool accounting
+    pool.add_withdrawal(transfer.amount)?;
+    pool.increment_transfers_resolved(transfer.created_at)?;
+
+    emit!(TransferRejected {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        sender: transfer.sender,
+        recipient: transfer.recipient,
+        amount: transfer.amount,
+        reason: if pool.full_events() { transfer.reject_reason } else { None },
+        transfer_fee_bps: pool.effective_fee_bps(transfer),
+        fee_burn_bps: pool.fee_burn_bps,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeReject<'info> {
+    /// Anyone can call this (permissionless)
+    pub caller: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sender's token account to receive refund (omit when a refund account override is set)
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Refund account override recorded on the transfer, if any
+    #[account(mut)]
+    pub refund_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Soft-rejected transfer (closed to sender)
+    #[account(
+        mut,
+        close = sender,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+
+    /// CHECK: Sender receives rent refund on close.
+    #[account(
+        mut,
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub sender: AccountInfo<'info>,
+
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            transfer.sender.as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+use super::{FeeAccrued, route_fee_to_vault};
+
+/// Force-resolve a transfer that has outlived the pool's max lifetime
+/// (permissionless). The sender is refunded the amount minus the pool's
+/// stale fee, which is split between burn and collected fees like any fee.
+pub fn force_resolve<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ForceResolve<'info>>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate transfer is active and stale
+    transfer.validate_active()?;
+    let clock = Clock::get()?;
+    require!(
+        transfer.is_stale(clock.unix_timestamp),
+        HandshakeError::TransferNotStale
+    );
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
+    // Calculate refund
+    let stale_fee = transfer.calculate_stale_fee();
+    let refund = transfer
+        .amount
+        .checked_sub(stale_fee)
+        .ok_or(HandshakeError::CalculationError)?;
+
+    // Transfer the refund back to sender
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.pool_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.sender_token_account.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        pool_signer_seeds,
+    );
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        refund,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Burn the configured share of the fee, keep the rest as collected fees
+    let fee_burned = pool.calculate_fee_burn(stale_fee);
+    if fee_burned > 0 {
+        let burn_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let burn_cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            burn_accounts,
+            pool_signer_seeds,
+        );
+        burn(burn_cpi_ctx, fee_burned)?;
+    }
+    let fee_collected = stale_fee
+        .checked_sub(fee_burned)
+        .ok_or(HandshakeError::CalculationError)?;
+
+    // Move the collected share into the fee vault, if the pool has one
+    route_fee_to_vault(
+        pool,
+        &ctx.accounts.pool_token_account,
+        ctx.accounts.fee_vault.as_deref(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        fee_collected,
+    )?;
+
+    // Give a waived transfer's free-transfer allowance back
+    if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+        sender_profile.release_transfer(transfer);
+    }
+
+    // Update pool accounting
+    pool.add_withdrawal(transfer.amount)?;
+    if fee_collected > 0 {
+        pool.add_collected_fees(fee_collected)?;
+        emit!(FeeAccrued {
+            pool: pool.key(),
+            transfer: transfer.key(),
+            amount: fee_collected,
+            outcome: TransferStatus::ForceResolved,
+        });
+    }
+    pool.increment_transfers_resolved(transfer.created_at)?;
+
+    // Mark transfer as force-resolved
+    transfer.mark_as_force_resolved()?;
+
+    emit!(TransferForceResolved {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        sender: transfer.sender,
+        recipient: transfer.recipient,
+        amount: transfer.amount,
+        stale_fee,
+        fee_burned,
+        caller: ctx.accounts.caller.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ForceResolve<'info> {
+    /// Anyone can call this (permissionless)
+    pub caller: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation (mutable for fee burns)
+    #[account(
+        mut,
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sender's token account to receive refund
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Transfer account to force-resolve (closed to sender)
+    #[account(
+        mut,
+        close = sender,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+
+    /// CHECK: Sender receives rent refund on close.
+    #[account(
+        mut,
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub sender: AccountInfo<'info>,
+
+    /// Pool's fee vault (required when the pool has one)
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            transfer.sender.as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct TransferForceResolved {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Fee kept from `amount`; the sender received the rest
+    pub stale_fee: u64,
+    /// Share of `stale_fee` burned under the pool's fee_burn_bps
+    pub fee_burned: u64,
+    pub caller: Pubkey,
+}
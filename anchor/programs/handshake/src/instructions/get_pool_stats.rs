@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, constants::*};
+
+/// Read-only view of pool statistics, returned via return data
+pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStats> {
+    let pool = &ctx.accounts.pool;
+
+    Ok(PoolStats {
+        total_deposits: pool.total_deposits,
+        total_withdrawals: pool.total_withdrawals,
+        total_escrowed: pool.total_escrowed,
+        total_transfers_created: pool.total_transfers_created,
+        total_transfers_resolved: pool.total_transfers_resolved,
+        collected_fees: pool.collected_fees,
+        total_resolution_time: pool.total_resolution_time,
+        average_resolution_time: pool.average_resolution_time(),
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetPoolStats<'info> {
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PoolStats {
+    pub total_deposits: u64,
+    pub total_withdrawals: u64,
+    pub total_escrowed: u64,
+    pub total_transfers_created: u64,
+    pub total_transfers_resolved: u64,
+    pub collected_fees: u64,
+    /// Sum of resolution latencies, in seconds
+    pub total_resolution_time: u128,
+    /// total_resolution_time / total_transfers_resolved, in seconds
+    pub average_resolution_time: u64,
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use crate::{state::*, constants::*};
+use super::PoolCreated;
+
+/// Initialize the canonical pool for an (operator, mint) pair. The marker PDA
+/// derived from [CANONICAL_POOL_SEED, operator, mint] can only be created once,
+/// so each operator has at most one canonical pool per mint.
+pub fn init_canonical_pool(
+    ctx: Context<InitCanonicalPool>,
+    pool_id: Pubkey,
+    transfer_fee_bps: u16,
+    name: String,
+    uri: String,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Initialize pool account
+    pool.initialize(
+        ctx.bumps.pool,
+        pool_id,
+        ctx.accounts.operator.key(),
+        ctx.accounts.mint.key(),
+        transfer_fee_bps,
+    )?;
+
+    // Record the pool's display metadata
+    let pool_metadata = &mut ctx.accounts.pool_metadata;
+    pool_metadata.version = 1;
+    pool_metadata.bump = ctx.bumps.pool_metadata;
+    pool_metadata.pool = pool.key();
+    pool_metadata.set(name, uri)?;
+
+    // Record the canonical pool for lookups by (operator, mint)
+    let canonical_pool = &mut ctx.accounts.canonical_pool;
+    canonical_pool.version = 1;
+    canonical_pool.bump = ctx.bumps.canonical_pool;
+    canonical_pool.operator = pool.operator;
+    canonical_pool.mint = pool.mint;
+    canonical_pool.pool = pool.key();
+
+    emit!(PoolCreated {
+        pool: pool.key(),
+        pool_id,
+        operator: pool.operator,
+        mint: pool.mint,
+        transfer_fee_bps,
+        name: pool_metadata.name.clone(),
+        uri: pool_metadata.uri.clone(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: Pubkey, transfer_fee_bps: u16)]
+pub struct InitCanonicalPool<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    /// The token mint this pool will handle
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Canonical pool marker - PDA derived from operator and mint (fails if it exists)
+    #[account(
+        init,
+        payer = operator,
+        space = CanonicalPool::SPACE,
+        seeds = [
+            CANONICAL_POOL_SEED,
+            operator.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub canonical_pool: Box<Account<'info, CanonicalPool>>,
+
+    /// Pool account - PDA derived from pool_id
+    #[account(
+        init,
+        payer = operator,
+        space = Pool::SPACE,
+        seeds = [
+            POOL_SEED,
+            pool_id.as_ref()
+        ],
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool's token account - where escrowed funds are stored
+    #[account(
+        init,
+        payer = operator,
+        associated_token::mint = mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool metadata - PDA derived from the pool
+    #[account(
+        init,
+        payer = operator,
+        space = PoolMetadata::SPACE,
+        seeds = [
+            POOL_METADATA_SEED,
+            pool.key().as_ref()
+        ],
+        bump
+    )]
+    pub pool_metadata: Box<Account<'info, PoolMetadata>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
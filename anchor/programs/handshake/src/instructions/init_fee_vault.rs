@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+
+/// Create the pool's fee vault and route collected fees to it from now on
+/// (operator only). Fees already collected in the pool token account are
+/// moved over so the vault always holds exactly `collected_fees`. Once set,
+/// the vault cannot be removed.
+pub fn init_fee_vault<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, InitFeeVault<'info>>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    require!(
+        pool.fee_vault == Pubkey::default(),
+        HandshakeError::FeeVaultAlreadyInitialized
+    );
+
+    // Move fees already collected out of the escrow account
+    let migrated = pool.collected_fees;
+    if migrated > 0 {
+        let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+        let pool_signer_seeds = &[&pool_seeds[..]];
+
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.fee_vault.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            migrated,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    pool.fee_vault = ctx.accounts.fee_vault.key();
+
+    emit!(FeeVaultInitialized {
+        pool: pool.key(),
+        fee_vault: pool.fee_vault,
+        migrated,
+    });
+
+    Ok(())
+}
+
+/// Move `amount` of freshly collected fees from the pool token account into
+/// the pool's fee vault. A no-op for pools without a fee vault.
+pub(crate) fn route_fee_to_vault<'info>(
+    pool: &Account<'info, Pool>,
+    pool_token_account: &InterfaceAccount<'info, TokenAccount>,
+    fee_vault: Option<&InterfaceAccount<'info, TokenAccount>>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    additional_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    if pool.fee_vault == Pubkey::default() || amount == 0 {
+        return Ok(());
+    }
+    let fee_vault = fee_vault.ok_or(HandshakeError::FeeVaultRequired)?;
+    require_keys_eq!(fee_vault.key(), pool.fee_vault, HandshakeError::FeeVaultRequired);
+
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: pool_token_account.to_account_info(),
+        mint: mint.to_account_info(),
+        to: fee_vault.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        transfer_accounts,
+        pool_signer_seeds,
+    );
+    transfer_checked_with_hooks(cpi_ctx, additional_accounts, amount, mint.decimals)
+}
+
+#[derive(Accounts)]
+pub struct InitFeeVault<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Fee vault - PDA token account owned by the pool, kept apart from escrow
+    #[account(
+        init,
+        payer = operator,
+        seeds = [
+            FEE_VAULT_SEED,
+            pool.key().as_ref()
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct FeeVaultInitialized {
+    pub pool: Pubkey,
+    pub fee_vault: Pubkey,
+    /// Previously collected fees moved into the vault
+    pub migrated: u64,
+}
@@ -3,39 +3,33 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
-use crate::{state::*, errors::*, constants::*};
+use crate::{state::*, constants::*};
 
 /// Initialize a new escrow pool for a specific token
 pub fn init_pool(
     ctx: Context<InitPool>,
     pool_id: Pubkey,
     transfer_fee_bps: u16,
+    name: String,
+    uri: String,
 ) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
-    let clock = Clock::get()?;
-
-    // Validate fee configuration
-    require!(
-        transfer_fee_bps <= 10000,
-        HandshakeError::InvalidTransferFee
-    );
 
     // Initialize pool account
-    pool.version = 1;
-    pool.bump = ctx.bumps.pool;
-    pool.pool_id = pool_id;
-    pool.operator = ctx.accounts.operator.key();
-    pool.mint = ctx.accounts.mint.key();
-    pool.transfer_fee_bps = transfer_fee_bps;
+    pool.initialize(
+        ctx.bumps.pool,
+        pool_id,
+        ctx.accounts.operator.key(),
+        ctx.accounts.mint.key(),
+        transfer_fee_bps,
+    )?;
 
-    // Initialize tracking
-    pool.total_deposits = 0;
-    pool.total_withdrawals = 0;
-    pool.total_escrowed = 0;
-    pool.total_transfers_created = 0;
-    pool.total_transfers_resolved = 0;
-    pool.collected_fees = 0;
-    pool.is_paused = false;
+    // Record the pool's display metadata
+    let pool_metadata = &mut ctx.accounts.pool_metadata;
+    pool_metadata.version = 1;
+    pool_metadata.bump = ctx.bumps.pool_metadata;
+    pool_metadata.pool = pool.key();
+    pool_metadata.set(name, uri)?;
 
     emit!(PoolCreated {
         pool: pool.key(),
@@ -43,6 +37,8 @@ pub fn init_pool(
         operator: pool.operator,
         mint: pool.mint,
         transfer_fee_bps,
+        name: pool_metadata.name.clone(),
+        uri: pool_metadata.uri.clone(),
     });
 
     Ok(())
@@ -80,6 +76,19 @@ pub struct InitPool<'info> {
     )]
     pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Pool metadata - PDA derived from the pool
+    #[account(
+        init,
+        payer = operator,
+        space = PoolMetadata::SPACE,
+        seeds = [
+            POOL_METADATA_SEED,
+            pool.key().as_ref()
+        ],
+        bump
+    )]
+    pub pool_metadata: Box<Account<'info, PoolMetadata>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -94,4 +103,6 @@ pub struct PoolCreated {
     pub operator: Pubkey,
     pub mint: Pubkey,
     pub transfer_fee_bps: u16,
+    pub name: String,
+    pub uri: String,
 }
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, constants::*};
+
+/// Create a sender's profile in a pool (permissionless, payer funds the rent).
+/// Passing it to `create_transfer` spends the pool's free-transfer allowance;
+/// passing it to a refunding instruction gives a waived transfer's share back.
+pub fn init_sender_profile(ctx: Context<InitSenderProfile>) -> Result<()> {
+    let sender_profile = &mut ctx.accounts.sender_profile;
+    sender_profile.version = 1;
+    sender_profile.bump = ctx.bumps.sender_profile;
+    sender_profile.pool = ctx.accounts.pool.key();
+    sender_profile.sender = ctx.accounts.sender.key();
+    sender_profile.free_transfers_used = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitSenderProfile<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: Sender the profile tracks; only used as a seed.
+    pub sender: AccountInfo<'info>,
+
+    /// Sender profile - PDA derived from pool and sender
+    #[account(
+        init,
+        payer = payer,
+        space = SenderProfile::SPACE,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            sender.key().as_ref()
+        ],
+        bump
+    )]
+    pub sender_profile: Box<Account<'info, SenderProfile>>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+
+/// Create the pool's stake vault (operator only). Its bump is cached on the
+/// pool so deposits and withdrawals verify the vault without re-deriving it.
+pub fn init_stake_vault(ctx: Context<InitStakeVault>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    pool.stake_vault_bump = ctx.bumps.stake_vault;
+
+    emit!(StakeVaultInitialized {
+        pool: pool.key(),
+        stake_vault: ctx.accounts.stake_vault.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitStakeVault<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Stake vault - PDA token account owned by the pool, kept apart from escrow
+    #[account(
+        init,
+        payer = operator,
+        seeds = [
+            STAKE_SEED,
+            pool.key().as_ref()
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct StakeVaultInitialized {
+    pub pool: Pubkey,
+    pub stake_vault: Pubkey,
+}
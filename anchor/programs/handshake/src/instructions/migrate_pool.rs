@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use crate::{state::*, constants::*, errors::*};
+
+/// Grow a pool created under an older, smaller layout to the current one
+/// (permissionless, payer tops up the rent). Pool fields are fixed-size and
+/// only ever appended, so the old bytes stay in place and the new fields
+/// start zeroed.
+pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+
+    require!(
+        pool_info.owner == &crate::ID,
+        HandshakeError::InvalidMigrationAccount
+    );
+    let old_space = {
+        let data = pool_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && &data[..8] == Pool::DISCRIMINATOR,
+            HandshakeError::InvalidMigrationAccount
+        );
+        data.len()
+    };
+    require!(old_space < Pool::SPACE, HandshakeError::AccountAlreadyMigrated);
+
+    // Fund the larger account before growing it
+    let rent_due = Rent::get()?
+        .minimum_balance(Pool::SPACE)
+        .saturating_sub(pool_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: pool_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    pool_info.resize(Pool::SPACE)?;
+
+    let mut data = pool_info.try_borrow_mut_data()?;
+    let mut pool = Pool::try_deserialize(&mut &data[..])?;
+    let from_version = pool.version;
+    pool.version = POOL_VERSION;
+    if pool.period_started_at == 0 {
+        pool.period_started_at = Clock::get()?.unix_timestamp;
+    }
+    pool.try_serialize(&mut &mut data[..])?;
+
+    emit!(PoolMigrated {
+        pool: pool_info.key(),
+        from_version,
+        to_version: POOL_VERSION,
+        old_space: old_space as u64,
+        new_space: Pool::SPACE as u64,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Pool in an older layout, which `Account<Pool>` can't load;
+    /// owner and discriminator are checked in the handler.
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct PoolMigrated {
+    pub pool: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub old_space: u64,
+    pub new_space: u64,
+}
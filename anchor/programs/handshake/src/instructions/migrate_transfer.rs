@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use crate::{state::*, constants::*, errors::*};
+
+/// Grow a transfer created under an older, smaller layout to the current one
+/// (permissionless, payer tops up the rent). Every field added since v1 was
+/// appended after `keeper_tip`, where the old layout only held zeroed
+/// padding, so the zero-extended bytes decode as the new fields' defaults
+/// (None, false, 0, no milestones).
+pub fn migrate_transfer(ctx: Context<MigrateTransfer>) -> Result<()> {
+    let transfer_info = ctx.accounts.transfer.to_account_info();
+
+    require!(
+        transfer_info.owner == &crate::ID,
+        HandshakeError::InvalidMigrationAccount
+    );
+    let old_space = {
+        let data = transfer_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && &data[..8] == SecureTransfer::DISCRIMINATOR,
+            HandshakeError::InvalidMigrationAccount
+        );
+        data.len()
+    };
+    require!(
+        old_space < SecureTransfer::SPACE,
+        HandshakeError::AccountAlreadyMigrated
+    );
+
+    // Fund the larger account before growing it
+    let rent_due = Rent::get()?
+        .minimum_balance(SecureTransfer::SPACE)
+        .saturating_sub(transfer_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: transfer_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    transfer_info.resize(SecureTransfer::SPACE)?;
+
+    let mut data = transfer_info.try_borrow_mut_data()?;
+    let mut transfer = SecureTransfer::try_deserialize(&mut &data[..])?;
+    let from_version = transfer.version;
+    transfer.version = TRANSFER_VERSION;
+    transfer.try_serialize(&mut &mut data[..])?;
+
+    emit!(TransferMigrated {
+        transfer: transfer_info.key(),
+        from_version,
+        to_version: TRANSFER_VERSION,
+        old_space: old_space as u64,
+        new_space: SecureTransfer::SPACE as u64,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateTransfer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Transfer in an older layout, which `Account<SecureTransfer>`
+    /// can't load; owner and discriminator are checked in the handler.
+    #[account(mut)]
+    pub transfer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct TransferMigrated {
+    pub transfer: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub old_space: u64,
+    pub new_space: u64,
+}
@@ -10,6 +10,44 @@ mod destroy_transfer;
 mod pause_pool;
 mod reset_pool;
 mod close_pool;
+mod set_fee_burn_bps;
+mod set_transfer_fee_bps;
+mod batch_withdraw_fees;
+mod set_recipient_expectation;
+mod reject_transfer_by_signature;
+mod init_canonical_pool;
+mod create_transfer_committed;
+mod reveal_transfer;
+mod settle_transfer;
+mod set_defer_missing_refunds;
+mod claim_refund;
+mod get_pool_stats;
+mod update_pool_config;
+mod mutual_accept;
+mod force_resolve;
+mod undo_reject;
+mod finalize_reject;
+mod set_operator_fee_account;
+mod snapshot_period;
+mod deposit_operator_stake;
+mod withdraw_operator_stake;
+mod set_fee_override;
+mod set_milestones;
+mod release_milestone;
+mod init_fee_vault;
+mod reject_all_from_sender;
+mod extend_transfer;
+mod quote_net_received;
+mod update_recipient;
+mod propose_reconciliation;
+mod reconcile_accounting;
+mod withdraw_sol_fees;
+mod update_pool_metadata;
+mod reject_transfer_split_refund;
+mod init_sender_profile;
+mod migrate_pool;
+mod migrate_transfer;
+mod init_stake_vault;
 
 pub use init_pool::*;
 pub use create_transfer::*;
@@ -23,3 +61,41 @@ pub use destroy_transfer::*;
 pub use pause_pool::*;
 pub use reset_pool::*;
 pub use close_pool::*;
+pub use set_fee_burn_bps::*;
+pub use set_transfer_fee_bps::*;
+pub use batch_withdraw_fees::*;
+pub use set_recipient_expectation::*;
+pub use reject_transfer_by_signature::*;
+pub use init_canonical_pool::*;
+pub use create_transfer_committed::*;
+pub use reveal_transfer::*;
+pub use settle_transfer::*;
+pub use set_defer_missing_refunds::*;
+pub use claim_refund::*;
+pub use get_pool_stats::*;
+pub use update_pool_config::*;
+pub use mutual_accept::*;
+pub use force_resolve::*;
+pub use undo_reject::*;
+pub use finalize_reject::*;
+pub use set_operator_fee_account::*;
+pub use snapshot_period::*;
+pub use deposit_operator_stake::*;
+pub use withdraw_operator_stake::*;
+pub use set_fee_override::*;
+pub use set_milestones::*;
+pub use release_milestone::*;
+pub use init_fee_vault::*;
+pub use reject_all_from_sender::*;
+pub use extend_transfer::*;
+pub use quote_net_received::*;
+pub use update_recipient::*;
+pub use propose_reconciliation::*;
+pub use reconcile_accounting::*;
+pub use withdraw_sol_fees::*;
+pub use update_pool_metadata::*;
+pub use reject_transfer_split_refund::*;
+pub use init_sender_profile::*;
+pub use migrate_pool::*;
+pub use migrate_transfer::*;
+pub use init_stake_vault::*;
@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+use anchor_spl::memo::Memo;
+use anchor_spl::token_interface::{burn, Burn, TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+use super::{FeeAccrued, TransferClaimed, collect_sol_fee, route_fee_to_vault, notify_memo};
+
+/// Release a transfer to the recipient with both the operator and the
+/// recipient signing. This is the only way to release a transfer created
+/// with `requires_mutual`.
+pub fn mutual_accept<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, MutualAccept<'info>>,
+    claim_code: Option<[u8; 32]>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate the operator is sufficiently staked
+    pool.validate_operator_stake()?;
+
+    // Validate recipient can claim
+    transfer.validate_recipient_can_claim(ctx.accounts.recipient.key())?;
+    transfer.validate_claim_code(&transfer.key(), claim_code)?;
+    transfer.validate_revealed()?;
+    transfer.validate_no_milestones()?;
+
+    // Calculate fee and any change owed back to the sender
+    let (fee, net_amount, change) = pool.calculate_payout(transfer)?;
+
+    // Stale transfers can only be force-resolved
+    transfer.validate_not_stale()?;
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
+    // Transfer net amount to recipient using pool authority
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.pool_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        pool_signer_seeds,
+    );
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        net_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Attach the transfer memo to the payout, if the pool asks for it
+    notify_memo(pool, transfer, ctx.accounts.memo_program.as_ref())?;
+
+    // Return the excess of an exact-amount transfer to the sender
+    if change > 0 {
+        let sender_token_account = ctx
+            .accounts
+            .sender_token_account
+            .as_ref()
+            .ok_or(HandshakeError::SenderTokenAccountRequired)?;
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: sender_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            change,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    // Burn the configured share of the fee, keep the rest as collected fees
+    let fee_burned = pool.calculate_fee_burn(fee);
+    if fee_burned > 0 {
+        let burn_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let burn_cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            burn_accounts,
+            pool_signer_seeds,
+        );
+        burn(burn_cpi_ctx, fee_burned)?;
+    }
+    let fee_collected = fee
+        .checked_sub(fee_burned)
+        .ok_or(HandshakeError::CalculationError)?;
+
+    // Move the collected share into the fee vault, if the pool has one
+    route_fee_to_vault(
+        pool,
+        &ctx.accounts.pool_token_account,
+        ctx.accounts.fee_vault.as_deref(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        fee_collected,
+    )?;
+
+    // Update pool accounting
+    pool.add_withdrawal(transfer.amount)?;
+    collect_sol_fee(pool, transfer)?;
+    if fee_collected > 0 {
+        pool.add_collected_fees(fee_collected)?;
+        emit!(FeeAccrued {
+            pool: pool.key(),
+            transfer: transfer.key(),
+            amount: fee_collected,
+            outcome: TransferStatus::Claimed,
+        });
+    }
+    pool.increment_transfers_resolved(transfer.created_at)?;
+
+    // Mark transfer as claimed and close (rent to sender)
+    transfer.mark_as_claimed()?;
+
+    emit!(TransferClaimed {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        sender: transfer.sender,
+        recipient: transfer.recipient,
+        amount: transfer.amount,
+        fee,
+        fee_burned,
+        net_amount,
+        change,
+        transfer_fee_bps: pool.effective_fee_bps(transfer),
+        fee_burn_bps: pool.fee_burn_bps,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MutualAccept<'info> {
+    /// Pool operator co-signing the release
+    pub operator: Signer<'info>,
+
+    /// Recipient co-signing the release
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation (mutable for fee burns)
+    #[account(
+        mut,
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Recipient's token account to receive funds
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sender's token account to receive change (required when an exact-amount transfer has change)
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Transfer account to accept (closed to sender on success)
+    #[account(
+        mut,
+        close = sender,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+
+    /// CHECK: Sender receives rent refund on close.
+    #[account(
+        mut,
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub sender: AccountInfo<'info>,
+
+    /// Pool's fee vault (required when the pool has one)
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// SPL Memo program (required when the pool has notify_memo set)
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::program::Handshake;
+use crate::{state::*, errors::*, constants::*};
+
+/// Propose an accounting correction for a pool (program upgrade authority
+/// only). `active_escrow_total` is what open transfers and deferred refunds
+/// are actually owed. The correction can be applied with
+/// `reconcile_accounting` once `RECONCILE_TIMELOCK` has passed; proposing
+/// again replaces the pending proposal and restarts the timelock.
+pub fn propose_reconciliation(
+    ctx: Context<ProposeReconciliation>,
+    active_escrow_total: u64,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+
+    let clock = Clock::get()?;
+    let executable_at = clock
+        .unix_timestamp
+        .checked_add(RECONCILE_TIMELOCK)
+        .ok_or(HandshakeError::MathOverflow)?;
+
+    proposal.version = 1;
+    proposal.bump = ctx.bumps.proposal;
+    proposal.pool = ctx.accounts.pool.key();
+    proposal.admin = ctx.accounts.admin.key();
+    proposal.active_escrow_total = active_escrow_total;
+    proposal.proposed_at = clock.unix_timestamp;
+    proposal.executable_at = executable_at;
+
+    emit!(ReconciliationProposed {
+        pool: proposal.pool,
+        admin: proposal.admin,
+        active_escrow_total,
+        executable_at,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeReconciliation<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// This program, to locate its program data
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ HandshakeError::Unauthorized
+    )]
+    pub program: Program<'info, Handshake>,
+
+    /// Program data holding the upgrade authority
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ HandshakeError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The pool to correct
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pending proposal - PDA derived from pool (replaced if one exists)
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = ReconciliationProposal::SPACE,
+        seeds = [
+            RECONCILE_SEED,
+            pool.key().as_ref()
+        ],
+        bump
+    )]
+    pub proposal: Box<Account<'info, ReconciliationProposal>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct ReconciliationProposed {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub active_escrow_total: u64,
+    pub executable_at: i64,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::{state::*, constants::*};
+use crate::transfer_hook::mint_transfer_fee;
+
+/// Read-only quote of what a recipient receives when a transfer of `amount`
+/// is claimed: the pool fee comes off first, then the mint's Token-2022
+/// transfer fee is withheld from the payout. A pool charging its fee in SOL
+/// takes no tokens; the SOL fee the sender pays on top is quoted instead.
+/// Returned via return data.
+pub fn quote_net_received(ctx: Context<QuoteNetReceived>, amount: u64) -> Result<NetQuote> {
+    let pool = &ctx.accounts.pool;
+
+    let (pool_fee, sol_fee_lamports) = if pool.fee_in_sol {
+        (0, pool.sol_fee_lamports)
+    } else {
+        (pool.calculate_transfer_fee(amount), 0)
+    };
+    let net_amount = amount.saturating_sub(pool_fee);
+    let mint_fee = mint_transfer_fee(
+        &ctx.accounts.mint.to_account_info(),
+        Clock::get()?.epoch,
+        net_amount,
+    )?;
+
+    Ok(NetQuote {
+        amount,
+        pool_fee,
+        net_amount,
+        mint_fee,
+        net_received: net_amount.saturating_sub(mint_fee),
+        sol_fee_lamports,
+    })
+}
+
+#[derive(Accounts)]
+pub struct QuoteNetReceived<'info> {
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The pool's mint, read for its transfer-fee config
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct NetQuote {
+    pub amount: u64,
+    /// Pool fee on `amount`
+    pub pool_fee: u64,
+    /// What the pool pays out: amount - pool_fee
+    pub net_amount: u64,
+    /// Withheld by the mint's transfer-fee extension on the payout
+    pub mint_fee: u64,
+    /// What lands in the recipient's account: net_amount - mint_fee
+    pub net_received: u64,
+    /// SOL fee the sender pays at creation when the pool charges fees in SOL
+    pub sol_fee_lamports: u64,
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+use crate::program::Handshake;
+use crate::{state::*, errors::*, constants::*};
+
+/// Apply a timelocked accounting correction (program upgrade authority only).
+/// `total_escrowed` is set to the proposal's active escrow total, and
+/// `collected_fees` to what the pool actually holds beyond it: the fee
+/// vault balance if the pool has one, else the pool token account balance
+/// minus the active escrow. The proposal is closed to the admin.
+pub fn reconcile_accounting(ctx: Context<ReconcileAccounting>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let proposal = &ctx.accounts.proposal;
+
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp >= proposal.executable_at,
+        HandshakeError::TimelockActive
+    );
+
+    let pool_token_balance = ctx.accounts.pool_token_account.amount;
+    let active_escrow_total = proposal.active_escrow_total;
+    let escrow_surplus = pool_token_balance
+        .checked_sub(active_escrow_total)
+        .ok_or(HandshakeError::EscrowMismatch)?;
+
+    // Fees sit in the fee vault if the pool routes them there
+    let new_collected_fees = if pool.fee_vault != Pubkey::default() {
+        let fee_vault = ctx
+            .accounts
+            .fee_vault
+            .as_ref()
+            .ok_or(HandshakeError::FeeVaultRequired)?;
+        require_keys_eq!(fee_vault.key(), pool.fee_vault, HandshakeError::FeeVaultRequired);
+        fee_vault.amount
+    } else {
+        escrow_surplus
+    };
+
+    let old_total_escrowed = pool.total_escrowed;
+    let old_collected_fees = pool.collected_fees;
+    pool.total_escrowed = active_escrow_total;
+    pool.collected_fees = new_collected_fees;
+
+    emit!(AccountingReconciled {
+        pool: pool.key(),
+        admin: ctx.accounts.admin.key(),
+        pool_token_balance,
+        active_escrow_total,
+        old_total_escrowed,
+        new_total_escrowed: pool.total_escrowed,
+        old_collected_fees,
+        new_collected_fees,
+        proposed_at: proposal.proposed_at,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReconcileAccounting<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// This program, to locate its program data
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ HandshakeError::Unauthorized
+    )]
+    pub program: Program<'info, Handshake>,
+
+    /// Program data holding the upgrade authority
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ HandshakeError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The pool to correct
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool's token account
+    #[account(
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool's fee vault (required when the pool has one)
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Timelocked proposal to apply (closed to admin)
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            RECONCILE_SEED,
+            pool.key().as_ref()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Box<Account<'info, ReconciliationProposal>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct AccountingReconciled {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    /// Pool token account balance the correction was computed from
+    pub pool_token_balance: u64,
+    pub active_escrow_total: u64,
+    pub old_total_escrowed: u64,
+    pub new_total_escrowed: u64,
+    pub old_collected_fees: u64,
+    pub new_collected_fees: u64,
+    pub proposed_at: i64,
+}
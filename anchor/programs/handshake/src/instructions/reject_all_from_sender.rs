@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::has_transfer_hook;
+use super::{TransferRejected, TransferRejectPending};
+
+/// Reject every in-flight transfer from one sender (operator only)
+///
+/// Remaining accounts are the sender's transfer accounts, all writable. At
+/// most `MAX_BATCH_SIZE` are accepted. Each is refunded in full to the
+/// sender's ATA and closed, or held as RejectedPending when the pool has a
+/// reject undo window. Transfers with a refund account override, and mints
+/// with transfer hooks, must be rejected one at a time with `reject_transfer`.
+pub fn reject_all_from_sender<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RejectAllFromSender<'info>>,
+    reason: Option<u8>,
+) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    require!(!remaining.is_empty(), HandshakeError::InvalidRemainingAccounts);
+    require!(
+        remaining.len() <= MAX_BATCH_SIZE,
+        HandshakeError::BatchTooLarge
+    );
+
+    // Remaining accounts carry the batch, leaving no room for a mint's
+    // transfer hook accounts
+    require!(
+        !has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+        HandshakeError::TransferHookNotAllowed
+    );
+
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate the operator is sufficiently staked
+    pool.validate_operator_stake()?;
+
+    let sender = ctx.accounts.sender.key();
+    let clock = Clock::get()?;
+    let mut pool_token_balance = ctx.accounts.pool_token_account.amount;
+
+    for transfer_info in remaining {
+        require!(transfer_info.is_writable, HandshakeError::InvalidRemainingAccounts);
+
+        let mut transfer = Account::<SecureTransfer>::try_from(transfer_info)?;
+
+        // Validate the transfer belongs to this pool and sender
+        require_keys_eq!(transfer.pool, pool.key(), HandshakeError::InvalidRemainingAccounts);
+        require_keys_eq!(transfer.sender, sender, HandshakeError::Unauthorized);
+        require!(
+            transfer.refund_token_account.is_none(),
+            HandshakeError::InvalidRefundAccount
+        );
+
+        // Validate transfer is active
+        transfer.validate_active()?;
+
+        // Stale transfers can only be force-resolved
+        transfer.validate_not_stale()?;
+
+        // Abort rather than close if the pool no longer backs this escrow
+        pool.validate_escrow(pool_token_balance, transfer.amount)?;
+
+        // Soft reject: hold the funds for the undo window instead of refunding
+        if pool.reject_undo_window > 0 {
+            let finalize_after = clock
+                .unix_timestamp
+                .checked_add(pool.reject_undo_window)
+                .ok_or(HandshakeError::MathOverflow)?;
+            transfer.mark_as_reject_pending(reason, finalize_after)?;
+            transfer.exit(ctx.program_id)?;
+
+            emit!(TransferRejectPending {
+                transfer: transfer.key(),
+                pool: pool.key(),
+                reason: if pool.full_events() { reason } else { None },
+                finalize_after,
+            });
+            continue;
+        }
+
+        // Transfer full amount back to sender (no fee on rejection)
+        let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+        let pool_signer_seeds = &[&pool_seeds[..]];
+
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.sender_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked(cpi_ctx, transfer.amount, ctx.accounts.mint.decimals)?;
+
+        pool_token_balance = pool_token_balance
+            .checked_sub(transfer.amount)
+            .ok_or(HandshakeError::EscrowMismatch)?;
+
+        // Give a waived transfer's free-transfer allowance back
+        if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+            sender_profile.release_transfer(&transfer);
+        }
+
+        // Update pool accounting
+        pool.add_withdrawal(transfer.amount)?;
+        pool.increment_transfers_resolved(transfer.created_at)?;
+
+        // Mark transfer as rejected and close (rent to sender)
+        transfer.mark_as_rejected()?;
+
+        emit!(TransferRejected {
+            transfer: transfer.key(),
+            pool: pool.key(),
+            sender: transfer.sender,
+            recipient: transfer.recipient,
+            amount: transfer.amount,
+            reason: if pool.full_events() { reason } else { None },
+            transfer_fee_bps: pool.effective_fee_bps(&transfer),
+            fee_burn_bps: pool.fee_burn_bps,
+        });
+
+        transfer.close(ctx.accounts.sender.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RejectAllFromSender<'info> {
+    pub operator: Signer<'info>,
+
+    /// The pool the transfers belong to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sender's token account to receive the refunds
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Sender whose transfers are rejected; receives rent refunds on close.
+    #[account(mut)]
+    pub sender: AccountInfo<'info>,
+
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            sender.key().as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
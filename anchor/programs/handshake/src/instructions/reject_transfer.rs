@@ -1,44 +1,221 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
 use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
 
-/// Reject a transfer as the operator (full refund to sender, no fee)
+/// Reject a transfer as the operator (full refund to sender, no fee).
+/// A refund token account set at creation takes precedence over the ATA.
+/// If that account doesn't exist or is frozen and the pool defers missing
+/// refunds, the refund is recorded in a PendingRefund for `claim_refund` instead.
+/// If the pool has a reject undo window, nothing is refunded yet: the
+/// transfer is held as RejectedPending for `undo_reject` / `finalize_reject`.
 pub fn reject_transfer<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, RejectTransfer<'info>>,
     reason: Option<u8>,
 ) -> Result<()> {
-    let pool = &mut ctx.accounts.pool;
-    let transfer = &mut ctx.accounts.transfer;
-
     // Validate operator
     require!(
-        ctx.accounts.operator.key() == pool.operator,
+        ctx.accounts.operator.key() == ctx.accounts.pool.operator,
         HandshakeError::Unauthorized
     );
 
+    let accounts = &mut *ctx.accounts;
+    process_reject(
+        RejectAccounts {
+            pool: &mut accounts.pool,
+            transfer: &mut accounts.transfer,
+            mint: &accounts.mint,
+            pool_token_account: &accounts.pool_token_account,
+            sender_token_account: accounts.sender_token_account.to_account_info(),
+            refund_token_account: accounts
+                .refund_token_account
+                .as_ref()
+                .map(|account| account.to_account_info()),
+            pending_refund: accounts.pending_refund.as_deref_mut(),
+            pending_refund_bump: ctx.bumps.pending_refund,
+            sender_profile: accounts.sender_profile.as_deref_mut(),
+            sender: accounts.sender.to_account_info(),
+            token_program: &accounts.token_program,
+        },
+        ctx.remaining_accounts,
+        reason,
+    )
+}
+
+/// Accounts an authorized reject works on, whoever authorized it
+pub(crate) struct RejectAccounts<'a, 'info> {
+    pub pool: &'a mut Account<'info, Pool>,
+    pub transfer: &'a mut Account<'info, SecureTransfer>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub pool_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub sender_token_account: AccountInfo<'info>,
+    pub refund_token_account: Option<AccountInfo<'info>>,
+    pub pending_refund: Option<&'a mut Account<'info, PendingRefund>>,
+    pub pending_refund_bump: Option<u8>,
+    pub sender_profile: Option<&'a mut Account<'info, SenderProfile>>,
+    pub sender: AccountInfo<'info>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Reject a transfer once the caller has authorized the operator: checks
+/// stake and transfer state, then soft-rejects, refunds or defers the refund
+/// and closes the transfer to the sender. Shared by `reject_transfer` and
+/// `reject_transfer_by_signature` so both resolve identically.
+pub(crate) fn process_reject<'info>(
+    accounts: RejectAccounts<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    reason: Option<u8>,
+) -> Result<()> {
+    let RejectAccounts {
+        pool,
+        transfer,
+        mint,
+        pool_token_account,
+        sender_token_account,
+        refund_token_account,
+        pending_refund,
+        pending_refund_bump,
+        sender_profile,
+        sender,
+        token_program,
+    } = accounts;
+
+    // Validate the operator is sufficiently staked
+    pool.validate_operator_stake()?;
+
     // Validate transfer is active
     transfer.validate_active()?;
 
-    // Transfer full amount back to sender (no fee on rejection)
-    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
-    let pool_signer_seeds = &[&pool_seeds[..]];
+    // Defense in depth: the account constraints already pin these owners
+    require!(
+        pool_token_account.owner == pool.key(),
+        HandshakeError::InvalidTokenAccountOwner
+    );
+    require!(
+        sender_token_account.key()
+            == get_associated_token_address_with_program_id(
+                &transfer.sender,
+                &pool.mint,
+                token_program.key,
+            ),
+        HandshakeError::InvalidTokenAccountOwner
+    );
+
+    // Stale transfers can only be force-resolved
+    transfer.validate_not_stale()?;
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(pool_token_account.amount, transfer.amount)?;
 
-    let transfer_accounts = TransferChecked {
-        from: ctx.accounts.pool_token_account.to_account_info(),
-        mint: ctx.accounts.mint.to_account_info(),
-        to: ctx.accounts.sender_token_account.to_account_info(),
-        authority: pool.to_account_info(),
+    // Soft reject: hold the funds for the undo window instead of refunding
+    if pool.reject_undo_window > 0 {
+        require!(
+            pending_refund.is_none(),
+            HandshakeError::InvalidPendingRefund
+        );
+        let finalize_after = Clock::get()?
+            .unix_timestamp
+            .checked_add(pool.reject_undo_window)
+            .ok_or(HandshakeError::MathOverflow)?;
+        transfer.mark_as_reject_pending(reason, finalize_after)?;
+
+        emit!(TransferRejectPending {
+            transfer: transfer.key(),
+            pool: pool.key(),
+            reason: if pool.full_events() { reason } else { None },
+            finalize_after,
+        });
+        return Ok(());
+    }
+
+    // Refund to the sender's override account if one was set, otherwise their
+    // ATA; the ATA must belong to the sender, an override is pinned by address
+    let (refund_destination, owner) = match transfer.refund_token_account {
+        Some(expected) => {
+            let refund_token_account =
+                refund_token_account.ok_or(HandshakeError::InvalidRefundAccount)?;
+            require_keys_eq!(
+                refund_token_account.key(),
+                expected,
+                HandshakeError::InvalidRefundAccount
+            );
+            (refund_token_account, None)
+        }
+        None => (sender_token_account, Some(transfer.sender)),
     };
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_accounts,
-        pool_signer_seeds,
-    );
-    transfer_checked(cpi_ctx, transfer.amount, ctx.accounts.mint.decimals)?;
+
+    // The refund is only deferred when that account truly can't take it
+    let refund_destination =
+        can_receive_refund(&refund_destination, owner, &pool.mint, token_program)?
+            .then_some(refund_destination);
+
+    match refund_destination {
+        Some(refund_destination) => {
+            require!(
+                pending_refund.is_none(),
+                HandshakeError::InvalidPendingRefund
+            );
+
+            // Transfer full amount back to sender (no fee on rejection)
+            let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+            let pool_signer_seeds = &[&pool_seeds[..]];
+
+            let transfer_accounts = TransferChecked {
+                from: pool_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: refund_destination,
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                transfer_accounts,
+                pool_signer_seeds,
+            );
+            transfer_checked_with_hooks(
+                cpi_ctx,
+                remaining_accounts,
+                transfer.amount,
+                mint.decimals,
+            )?;
+
+            pool.add_withdrawal(transfer.amount)?;
+        }
+        None => {
+            // Sender can't receive tokens; keep them escrowed as a claimable refund
+            require!(
+                pool.defer_missing_refunds,
+                HandshakeError::RefundDeferralDisabled
+            );
+            let pending_refund = pending_refund.ok_or(HandshakeError::InvalidPendingRefund)?;
+
+            pending_refund.version = 1;
+            pending_refund.bump = pending_refund_bump.ok_or(HandshakeError::InvalidPendingRefund)?;
+            pending_refund.pool = pool.key();
+            pending_refund.transfer = transfer.key();
+            pending_refund.sender = transfer.sender;
+            pending_refund.amount = transfer.amount;
+            pending_refund.created_at = Clock::get()?.unix_timestamp;
+
+            pool.add_pending_refund()?;
+
+            emit!(RefundDeferred {
+                pool: pool.key(),
+                transfer: transfer.key(),
+                sender: transfer.sender,
+                pending_refund: pending_refund.key(),
+                amount: transfer.amount,
+            });
+        }
+    }
+
+    // Give a waived transfer's free-transfer allowance back, deferred refunds included
+    if let Some(sender_profile) = sender_profile {
+        sender_profile.release_transfer(transfer);
+    }
 
     // Update pool accounting
-    pool.add_withdrawal(transfer.amount)?;
-    pool.increment_transfers_resolved()?;
+    pool.increment_transfers_resolved(transfer.created_at)?;
 
     // Mark transfer as rejected
     transfer.mark_as_rejected()?;
@@ -49,12 +226,65 @@ pub fn reject_transfer<'a, 'b, 'c, 'info>(
         sender: transfer.sender,
         recipient: transfer.recipient,
         amount: transfer.amount,
-        reason,
+        reason: if pool.full_events() { reason } else { None },
+        transfer_fee_bps: pool.effective_fee_bps(transfer),
+        fee_burn_bps: pool.fee_burn_bps,
     });
 
+    transfer.close(sender)?;
+
     Ok(())
 }
 
+/// Whether a refund token account exists and isn't frozen, i.e. can take a
+/// refund. `owner`, when given, must own it; it must hold the pool mint.
+fn can_receive_refund(
+    token_account: &AccountInfo,
+    owner: Option<Pubkey>,
+    mint: &Pubkey,
+    token_program: &Interface<TokenInterface>,
+) -> Result<bool> {
+    if token_account.data_is_empty() {
+        return Ok(false);
+    }
+    require!(
+        token_account.owner == token_program.key,
+        HandshakeError::InvalidTokenAccountOwner
+    );
+    let data = token_account.try_borrow_data()?;
+    let account = TokenAccount::try_deserialize(&mut &data[..])?;
+    require!(account.mint == *mint, HandshakeError::InvalidRefundAccount);
+    if let Some(owner) = owner {
+        require!(
+            account.owner == owner,
+            HandshakeError::InvalidTokenAccountOwner
+        );
+    }
+    Ok(!account.is_frozen())
+}
+
+/// Refund to the sender's override account if one was set, otherwise their
+/// ATA; `None` when the sender's ATA can't receive it
+pub(crate) fn refund_destination<'info>(
+    transfer: &SecureTransfer,
+    mint: &Pubkey,
+    sender_token_account: Option<AccountInfo<'info>>,
+    refund_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+) -> Result<Option<AccountInfo<'info>>> {
+    match transfer.refund_token_account {
+        Some(expected) => {
+            let refund_token_account =
+                refund_token_account.ok_or(HandshakeError::InvalidRefundAccount)?;
+            require!(
+                refund_token_account.key() == expected && refund_token_account.mint == *mint,
+                HandshakeError::InvalidRefundAccount
+            );
+            Ok(Some(refund_token_account.to_account_info()))
+        }
+        None => Ok(sender_token_account),
+    }
+}
+
 #[derive(Accounts)]
 pub struct RejectTransfer<'info> {
     #[account(mut)]
@@ -86,19 +316,33 @@ pub struct RejectTransfer<'info> {
     )]
     pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Sender's token account to receive refund
+    /// CHECK: Sender's ATA to receive the refund. It may not exist (or be
+    /// frozen), in which case the refund can be deferred; checked in the handler.
+    #[account(mut)]
+    pub sender_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Refund account override recorded on the transfer, if any. It may
+    /// have been closed (or frozen), in which case the refund can be deferred;
+    /// checked in the handler.
+    #[account(mut)]
+    pub refund_token_account: Option<UncheckedAccount<'info>>,
+
+    /// Deferred refund record, created only when the refund account can't take the refund
     #[account(
-        mut,
-        associated_token::mint = pool.mint,
-        associated_token::authority = transfer.sender,
-        associated_token::token_program = token_program
+        init,
+        payer = operator,
+        space = PendingRefund::SPACE,
+        seeds = [
+            REFUND_SEED,
+            transfer.key().as_ref()
+        ],
+        bump
     )]
-    pub sender_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub pending_refund: Option<Box<Account<'info, PendingRefund>>>,
 
-    /// Transfer account to reject (closed to sender)
+    /// Transfer account to reject (closed to sender unless soft-rejected)
     #[account(
         mut,
-        close = sender,
         constraint = transfer.pool == pool.key()
     )]
     pub transfer: Box<Account<'info, SecureTransfer>>,
@@ -110,7 +354,20 @@ pub struct RejectTransfer<'info> {
     )]
     pub sender: AccountInfo<'info>,
 
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            transfer.sender.as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Option<Program<'info, System>>,
 }
 
 #[event]
@@ -121,4 +378,26 @@ pub struct TransferRejected {
     pub recipient: Pubkey,
     pub amount: u64,
     pub reason: Option<u8>,
+    /// Fee rate (bps) the transfer would have been charged after any override,
+    /// locked rate, SOL fee or waiver (rejections are not charged)
+    pub transfer_fee_bps: u16,
+    pub fee_burn_bps: u16,
+}
+
+#[event]
+pub struct RefundDeferred {
+    pub pool: Pubkey,
+    pub transfer: Pubkey,
+    pub sender: Pubkey,
+    pub pending_refund: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TransferRejectPending {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub reason: Option<u8>,
+    /// When `finalize_reject` can refund the sender
+    pub finalize_after: i64,
 }
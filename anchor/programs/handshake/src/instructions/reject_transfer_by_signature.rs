@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use solana_sdk_ids::sysvar::instructions as instructions_sysvar;
+use crate::{state::*, errors::*, constants::*};
+use crate::signature::{reject_message, verify_ed25519_instruction};
+use super::{process_reject, RejectAccounts};
+
+/// Reject a transfer on the operator's behalf. A relayer submits and pays for
+/// the transaction; the operator's intent is proven by an Ed25519 signature
+/// over the reject message, verified via the instructions sysvar. Once
+/// authorized, the reject resolves exactly as `reject_transfer` would.
+pub fn reject_transfer_by_signature<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RejectTransferBySignature<'info>>,
+    reason: Option<u8>,
+    operator_nonce: u64,
+) -> Result<()> {
+    let transfer_key = ctx.accounts.transfer.key();
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate the operator's signed authorization
+    let message = reject_message(&transfer_key, reason, operator_nonce);
+    verify_ed25519_instruction(
+        &ctx.accounts.instructions_sysvar.to_account_info(),
+        &pool.operator,
+        &message,
+    )?;
+    pool.consume_operator_nonce(operator_nonce)?;
+
+    let accounts = &mut *ctx.accounts;
+    process_reject(
+        RejectAccounts {
+            pool: &mut accounts.pool,
+            transfer: &mut accounts.transfer,
+            mint: &accounts.mint,
+            pool_token_account: &accounts.pool_token_account,
+            sender_token_account: accounts.sender_token_account.to_account_info(),
+            refund_token_account: accounts
+                .refund_token_account
+                .as_ref()
+                .map(|account| account.to_account_info()),
+            pending_refund: accounts.pending_refund.as_deref_mut(),
+            pending_refund_bump: ctx.bumps.pending_refund,
+            sender_profile: accounts.sender_profile.as_deref_mut(),
+            sender: accounts.sender.to_account_info(),
+            token_program: &accounts.token_program,
+        },
+        ctx.remaining_accounts,
+        reason,
+    )?;
+
+    emit!(OperatorActionRelayed {
+        pool: ctx.accounts.pool.key(),
+        transfer: transfer_key,
+        relayer: ctx.accounts.relayer.key(),
+        operator_nonce,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RejectTransferBySignature<'info> {
+    /// Relayer submitting the operator's signed authorization (pays fees)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Sender's ATA to receive the refund. It may not exist (or be
+    /// frozen), in which case the refund can be deferred; checked in the handler.
+    #[account(mut)]
+    pub sender_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Refund account override recorded on the transfer, if any. It may
+    /// have been closed (or frozen), in which case the refund can be deferred;
+    /// checked in the handler.
+    #[account(mut)]
+    pub refund_token_account: Option<UncheckedAccount<'info>>,
+
+    /// Deferred refund record, created only when the refund account can't take the refund
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingRefund::SPACE,
+        seeds = [
+            REFUND_SEED,
+            transfer.key().as_ref()
+        ],
+        bump
+    )]
+    pub pending_refund: Option<Box<Account<'info, PendingRefund>>>,
+
+    /// Transfer account to reject (closed to sender unless soft-rejected)
+    #[account(
+        mut,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+
+    /// CHECK: Sender receives rent refund on close.
+    #[account(
+        mut,
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub sender: AccountInfo<'info>,
+
+    /// CHECK: Instructions sysvar, used to read the Ed25519 precompile instruction.
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            transfer.sender.as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[event]
+pub struct OperatorActionRelayed {
+    pub pool: Pubkey,
+    pub transfer: Pubkey,
+    pub relayer: Pubkey,
+    pub operator_nonce: u64,
+}
@@ -0,0 +1,238 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::has_transfer_hook;
+use super::TransferRejected;
+
+/// Reject a transfer with its refund split across several token accounts
+/// (operator, co-signed by the sender who chooses the split)
+///
+/// Remaining accounts are the destination token accounts, writable and in
+/// the same order as `splits`. The bps must sum to 10000; each destination
+/// gets its floor share and the last takes the rounding remainder, so the
+/// payouts add up to exactly the escrowed amount. Not available for pools
+/// with a reject undo window, transfers with a refund account override, or
+/// mints with transfer hooks.
+pub fn reject_transfer_split_refund<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RejectTransferSplitRefund<'info>>,
+    reason: Option<u8>,
+    splits: Vec<RefundSplit>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate the operator is sufficiently staked
+    pool.validate_operator_stake()?;
+
+    // Validate transfer is active
+    transfer.validate_active()?;
+
+    // Stale transfers can only be force-resolved
+    transfer.validate_not_stale()?;
+
+    // Soft rejects refund later via finalize_reject, which can't split
+    require!(pool.reject_undo_window == 0, HandshakeError::InvalidRefundSplit);
+    require!(
+        transfer.refund_token_account.is_none(),
+        HandshakeError::InvalidRefundAccount
+    );
+
+    // Remaining accounts carry the split destinations, leaving no room for a
+    // mint's transfer hook accounts
+    require!(
+        !has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+        HandshakeError::TransferHookNotAllowed
+    );
+
+    // Validate the split
+    let remaining = ctx.remaining_accounts;
+    require!(
+        !splits.is_empty() && splits.len() <= MAX_REFUND_SPLITS,
+        HandshakeError::InvalidRefundSplit
+    );
+    require!(
+        remaining.len() == splits.len(),
+        HandshakeError::InvalidRemainingAccounts
+    );
+    let total_bps = splits
+        .iter()
+        .try_fold(0u16, |total, split| {
+            if split.bps == 0 {
+                return None;
+            }
+            total.checked_add(split.bps)
+        })
+        .ok_or(HandshakeError::InvalidRefundSplit)?;
+    require!(total_bps == 10000, HandshakeError::InvalidRefundSplit);
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    let mut destinations = Vec::with_capacity(splits.len());
+    let mut amounts = Vec::with_capacity(splits.len());
+    let mut refunded: u64 = 0;
+    for (i, (split, destination_info)) in splits.iter().zip(remaining).enumerate() {
+        require_keys_eq!(
+            destination_info.key(),
+            split.token_account,
+            HandshakeError::InvalidRemainingAccounts
+        );
+        require!(destination_info.is_writable, HandshakeError::InvalidRemainingAccounts);
+
+        // Validate the destination holds the pool mint
+        let destination = InterfaceAccount::<TokenAccount>::try_from(destination_info)?;
+        require_keys_eq!(destination.mint, pool.mint, HandshakeError::InvalidRefundSplit);
+
+        // The last destination takes the rounding remainder
+        let amount = if i == splits.len() - 1 {
+            transfer
+                .amount
+                .checked_sub(refunded)
+                .ok_or(HandshakeError::CalculationError)?
+        } else {
+            (transfer.amount as u128)
+                .checked_mul(split.bps as u128)
+                .and_then(|share| share.checked_div(10000))
+                .ok_or(HandshakeError::MathOverflow)? as u64
+        };
+
+        if amount > 0 {
+            let transfer_accounts = TransferChecked {
+                from: ctx.accounts.pool_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: destination_info.clone(),
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                pool_signer_seeds,
+            );
+            transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        }
+
+        refunded = refunded
+            .checked_add(amount)
+            .ok_or(HandshakeError::MathOverflow)?;
+        destinations.push(split.token_account);
+        amounts.push(amount);
+    }
+
+    // Give a waived transfer's free-transfer allowance back
+    if let Some(sender_profile) = ctx.accounts.sender_profile.as_deref_mut() {
+        sender_profile.release_transfer(transfer);
+    }
+
+    // Update pool accounting
+    pool.add_withdrawal(transfer.amount)?;
+    pool.increment_transfers_resolved(transfer.created_at)?;
+
+    // Mark transfer as rejected
+    transfer.mark_as_rejected()?;
+
+    emit!(TransferRejected {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        sender: transfer.sender,
+        recipient: transfer.recipient,
+        amount: transfer.amount,
+        reason: if pool.full_events() { reason } else { None },
+        transfer_fee_bps: pool.effective_fee_bps(transfer),
+        fee_burn_bps: pool.fee_burn_bps,
+    });
+
+    emit!(RefundSplitPaid {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        destinations,
+        amounts,
+    });
+
+    Ok(())
+}
+
+/// One destination of a split refund and its share of the refund
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RefundSplit {
+    pub token_account: Pubkey,
+    pub bps: u16,
+}
+
+#[derive(Accounts)]
+pub struct RejectTransferSplitRefund<'info> {
+    pub operator: Signer<'info>,
+
+    /// Sender authorizing the split (receives rent refund on close)
+    #[account(
+        mut,
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub sender: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Transfer account to reject (closed to sender)
+    #[account(
+        mut,
+        close = sender,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+
+    /// Sender's profile in this pool, given back a waived transfer's free-transfer allowance
+    #[account(
+        mut,
+        seeds = [
+            SENDER_PROFILE_SEED,
+            pool.key().as_ref(),
+            transfer.sender.as_ref()
+        ],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Option<Box<Account<'info, SenderProfile>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct RefundSplitPaid {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    /// Destination token accounts, in split order
+    pub destinations: Vec<Pubkey>,
+    /// Amount paid to each destination; sums to the transfer amount
+    pub amounts: Vec<u64>,
+}
@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+use super::{FeeAccrued, collect_sol_fee, route_fee_to_vault};
+
+/// Release one milestone of a staged transfer to the recipient (operator only).
+/// The fee is taken from the released portion; once every milestone has been
+/// released the transfer is marked claimed and closed to the sender.
+pub fn release_milestone<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ReleaseMilestone<'info>>,
+    index: u8,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate the operator is sufficiently staked
+    pool.validate_operator_stake()?;
+
+    // Validate transfer can be released by the operator alone
+    transfer.validate_active()?;
+    transfer.validate_revealed()?;
+    transfer.validate_single_party_release()?;
+
+    // Stale transfers can only be force-resolved
+    transfer.validate_not_stale()?;
+
+    // Abort rather than release if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
+    // Calculate the portion and its fee
+    let amount = transfer.release_milestone(index as usize)?;
+    let fee = pool.calculate_fee_for(transfer, amount);
+    let net_amount = amount
+        .checked_sub(fee)
+        .ok_or(HandshakeError::CalculationError)?;
+
+    // Transfer net portion to recipient using pool authority
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.pool_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        pool_signer_seeds,
+    );
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        net_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // Burn the configured share of the fee, keep the rest as collected fees
+    let fee_burned = pool.calculate_fee_burn(fee);
+    if fee_burned > 0 {
+        let burn_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let burn_cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            burn_accounts,
+            pool_signer_seeds,
+        );
+        burn(burn_cpi_ctx, fee_burned)?;
+    }
+    let fee_collected = fee
+        .checked_sub(fee_burned)
+        .ok_or(HandshakeError::CalculationError)?;
+
+    // Move the collected share into the fee vault, if the pool has one
+    route_fee_to_vault(
+        pool,
+        &ctx.accounts.pool_token_account,
+        ctx.accounts.fee_vault.as_deref(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        fee_collected,
+    )?;
+
+    // Update pool accounting; the SOL fee is collected on the first release
+    pool.add_withdrawal(amount)?;
+    collect_sol_fee(pool, transfer)?;
+    if fee_collected > 0 {
+        pool.add_collected_fees(fee_collected)?;
+        emit!(FeeAccrued {
+            pool: pool.key(),
+            transfer: transfer.key(),
+            amount: fee_collected,
+            outcome: TransferStatus::Claimed,
+        });
+    }
+
+    let completed = transfer.all_milestones_released();
+
+    emit!(MilestoneReleased {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        recipient: transfer.recipient,
+        index,
+        amount,
+        fee,
+        fee_burned,
+        net_amount,
+        remaining: transfer.amount,
+        completed,
+    });
+
+    // Mark transfer as claimed and close (rent to sender) after the last milestone
+    if completed {
+        pool.increment_transfers_resolved(transfer.created_at)?;
+        transfer.mark_as_claimed()?;
+        transfer.close(ctx.accounts.sender.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReleaseMilestone<'info> {
+    pub operator: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation (mutable for fee burns)
+    #[account(
+        mut,
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Recipient's token account to receive the milestone
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Staged transfer (closed to sender once every milestone is released)
+    #[account(
+        mut,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+
+    /// CHECK: Sender receives rent refund on close.
+    #[account(
+        mut,
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub sender: AccountInfo<'info>,
+
+    /// Pool's fee vault (required when the pool has one)
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct MilestoneReleased {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub recipient: Pubkey,
+    pub index: u8,
+    /// Portion of the escrow released; `fee + net_amount == amount`
+    pub amount: u64,
+    pub fee: u64,
+    pub fee_burned: u64,
+    pub net_amount: u64,
+    /// Escrow left on the transfer after this release
+    pub remaining: u64,
+    pub completed: bool,
+}
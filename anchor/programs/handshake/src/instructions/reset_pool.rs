@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
 use crate::{state::*, errors::*, constants::*};
 
-/// Reset pool counters (operator only, requires no outstanding transfers)
+/// Reset pool counters (operator only, requires no outstanding transfers and,
+/// if the pool has a fee vault, that its fees have been withdrawn)
 pub fn reset_pool(ctx: Context<ResetPool>) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
 
@@ -17,6 +19,17 @@ pub fn reset_pool(ctx: Context<ResetPool>) -> Result<()> {
         HandshakeError::OutstandingTransfers
     );
 
+    // Fees left in the vault would be orphaned once collected_fees is zeroed
+    if pool.fee_vault != Pubkey::default() {
+        let fee_vault = ctx
+            .accounts
+            .fee_vault
+            .as_deref()
+            .ok_or(HandshakeError::FeeVaultRequired)?;
+        require_keys_eq!(fee_vault.key(), pool.fee_vault, HandshakeError::FeeVaultRequired);
+        require!(fee_vault.amount == 0, HandshakeError::FeeVaultNotEmpty);
+    }
+
     // Reset counters
     pool.total_deposits = 0;
     pool.total_withdrawals = 0;
@@ -24,6 +37,8 @@ pub fn reset_pool(ctx: Context<ResetPool>) -> Result<()> {
     pool.total_transfers_created = 0;
     pool.total_transfers_resolved = 0;
     pool.collected_fees = 0;
+    pool.total_resolution_time = 0;
+    pool.reset_period(Clock::get()?.unix_timestamp);
 
     emit!(PoolReset {
         pool: pool.key(),
@@ -46,6 +61,9 @@ pub struct ResetPool<'info> {
         bump = pool.bump
     )]
     pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool's fee vault (required when the pool has one)
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 }
 
 #[event]
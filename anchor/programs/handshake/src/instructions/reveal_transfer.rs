@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+
+/// Reveal a sealed transfer's amount (sender or recipient). The excess over
+/// the revealed amount is refunded to the sender and resolution proceeds on
+/// the revealed amount.
+pub fn reveal_transfer<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RevealTransfer<'info>>,
+    amount: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate caller is a party to the transfer
+    let revealer = ctx.accounts.revealer.key();
+    require!(
+        revealer == transfer.sender || revealer == transfer.recipient,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate transfer is active and the reveal matches the commitment
+    transfer.validate_active()?;
+    transfer.validate_no_milestones()?;
+    transfer.validate_amount_reveal(amount, &salt)?;
+    transfer.validate_not_stale()?;
+
+    RecipientExpectation::validate_amount(
+        &ctx.accounts.recipient_expectation.to_account_info(),
+        ctx.program_id,
+        amount,
+    )?;
+
+    // Refund the difference between the escrowed maximum and the real amount
+    let refund = transfer
+        .amount
+        .checked_sub(amount)
+        .ok_or(HandshakeError::CalculationError)?;
+
+    if refund > 0 {
+        let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+        let pool_signer_seeds = &[&pool_seeds[..]];
+
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.sender_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            refund,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        pool.add_withdrawal(refund)?;
+    }
+
+    transfer.amount = amount;
+    transfer.amount_commitment = None;
+
+    emit!(TransferRevealed {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        revealer,
+        amount,
+        refund,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealTransfer<'info> {
+    pub revealer: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sender's token account to receive the excess
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sealed transfer to reveal
+    #[account(
+        mut,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+
+    /// CHECK: Recipient's expectation PDA; may be uninitialized, deserialized in handler.
+    #[account(
+        seeds = [
+            EXPECTATION_SEED,
+            pool.key().as_ref(),
+            transfer.recipient.as_ref()
+        ],
+        bump
+    )]
+    pub recipient_expectation: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct TransferRevealed {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub revealer: Pubkey,
+    pub amount: u64,
+    /// Excess escrow returned to the sender
+    pub refund: u64,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Enable or disable deferred refunds for rejects to senders without a token account (operator only)
+pub fn set_defer_missing_refunds(
+    ctx: Context<SetDeferMissingRefunds>,
+    defer_missing_refunds: bool,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    pool.defer_missing_refunds = defer_missing_refunds;
+
+    emit!(DeferMissingRefundsUpdated {
+        pool: pool.key(),
+        defer_missing_refunds,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetDeferMissingRefunds<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+#[event]
+pub struct DeferMissingRefundsUpdated {
+    pub pool: Pubkey,
+    pub defer_missing_refunds: bool,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Set the share of collected fees that is burned (operator only)
+pub fn set_fee_burn_bps(ctx: Context<SetFeeBurnBps>, fee_burn_bps: u16) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate fee configuration
+    require!(fee_burn_bps <= 10000, HandshakeError::InvalidFeeConfig);
+
+    pool.fee_burn_bps = fee_burn_bps;
+
+    emit!(FeeBurnBpsUpdated {
+        pool: pool.key(),
+        fee_burn_bps,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeBurnBps<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+#[event]
+pub struct FeeBurnBpsUpdated {
+    pub pool: Pubkey,
+    pub fee_burn_bps: u16,
+}
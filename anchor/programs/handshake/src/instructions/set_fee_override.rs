@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Set a negotiated fee rate on a transfer, replacing the pool fee for it
+/// alone (operator and sender both sign). `None` restores the pool fee, or
+/// the rate an exact-amount transfer locked at creation.
+pub fn set_fee_override(ctx: Context<SetFeeOverride>, fee_override_bps: Option<u16>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate transfer is active
+    transfer.validate_active()?;
+
+    // The override is held to the same ceiling as the pool fee
+    if let Some(fee_override_bps) = fee_override_bps {
+        require!(
+            fee_override_bps <= 10000 - MIN_REFUND_BPS,
+            HandshakeError::InvalidFeeConfig
+        );
+    }
+    transfer.fee_override_bps = fee_override_bps;
+
+    // An exact-amount escrow must still cover the payout at the new rate
+    if transfer.exact_amount.is_some() {
+        pool.calculate_payout(transfer)?;
+    }
+
+    emit!(FeeOverrideSet {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        fee_override_bps,
+        effective_fee_bps: pool.effective_fee_bps(transfer),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeOverride<'info> {
+    pub operator: Signer<'info>,
+
+    pub sender: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Transfer to reprice
+    #[account(
+        mut,
+        constraint = transfer.pool == pool.key(),
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+}
+
+#[event]
+pub struct FeeOverrideSet {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub fee_override_bps: Option<u16>,
+    /// Rate the transfer will be charged at resolution (before any coupon discount)
+    pub effective_fee_bps: u16,
+}
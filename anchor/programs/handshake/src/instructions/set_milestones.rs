@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Split an active transfer into milestones, each releasing `bps` of the
+/// amount (sender only). The fractions must sum to 10000 and, once set,
+/// the transfer can only be paid out through `release_milestone`. Sealed
+/// transfers must be revealed first so the fractions apply to the real amount.
+pub fn set_milestones(ctx: Context<SetMilestones>, milestones_bps: Vec<u16>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    transfer.set_milestones(&milestones_bps)?;
+
+    emit!(MilestonesSet {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        milestones_bps,
+        milestone_total: transfer.milestone_total,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMilestones<'info> {
+    pub sender: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Transfer to stage
+    #[account(
+        mut,
+        constraint = transfer.pool == pool.key(),
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+}
+
+#[event]
+pub struct MilestonesSet {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub milestones_bps: Vec<u16>,
+    /// Amount the fractions are taken of
+    pub milestone_total: u64,
+}
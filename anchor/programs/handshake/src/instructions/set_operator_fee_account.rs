@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+
+/// Register the token account fee withdrawals are paid to (operator only).
+/// Omitting the account clears it, so fees go back to the operator's ATA.
+pub fn set_operator_fee_account(ctx: Context<SetOperatorFeeAccount>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    pool.operator_fee_account = ctx
+        .accounts
+        .fee_account
+        .as_ref()
+        .map(|account| account.key())
+        .unwrap_or_default();
+
+    emit!(OperatorFeeAccountUpdated {
+        pool: pool.key(),
+        operator_fee_account: pool.operator_fee_account,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetOperatorFeeAccount<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Token account to receive fees (omit to clear)
+    #[account(
+        token::mint = pool.mint,
+        token::token_program = token_program
+    )]
+    pub fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct OperatorFeeAccountUpdated {
+    pub pool: Pubkey,
+    /// `Pubkey::default()` when cleared
+    pub operator_fee_account: Pubkey,
+}
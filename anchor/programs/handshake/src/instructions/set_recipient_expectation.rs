@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, constants::*};
+
+/// Register or update the minimum amount the recipient expects (recipient only)
+pub fn set_recipient_expectation(
+    ctx: Context<SetRecipientExpectation>,
+    expected_amount: u64,
+) -> Result<()> {
+    let expectation = &mut ctx.accounts.recipient_expectation;
+
+    expectation.version = 1;
+    expectation.bump = ctx.bumps.recipient_expectation;
+    expectation.pool = ctx.accounts.pool.key();
+    expectation.recipient = ctx.accounts.recipient.key();
+    expectation.expected_amount = expected_amount;
+
+    emit!(RecipientExpectationSet {
+        pool: expectation.pool,
+        recipient: expectation.recipient,
+        expected_amount,
+    });
+
+    Ok(())
+}
+
+/// Remove the recipient's expectation (recipient only, rent refunded)
+pub fn close_recipient_expectation(ctx: Context<CloseRecipientExpectation>) -> Result<()> {
+    emit!(RecipientExpectationClosed {
+        pool: ctx.accounts.pool.key(),
+        recipient: ctx.accounts.recipient.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRecipientExpectation<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// The pool the expectation applies to
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Expectation account - PDA derived from pool and recipient
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        space = RecipientExpectation::SPACE,
+        seeds = [
+            EXPECTATION_SEED,
+            pool.key().as_ref(),
+            recipient.key().as_ref()
+        ],
+        bump
+    )]
+    pub recipient_expectation: Box<Account<'info, RecipientExpectation>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRecipientExpectation<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// The pool the expectation applies to
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Expectation account to close (rent to recipient)
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [
+            EXPECTATION_SEED,
+            pool.key().as_ref(),
+            recipient.key().as_ref()
+        ],
+        bump = recipient_expectation.bump
+    )]
+    pub recipient_expectation: Box<Account<'info, RecipientExpectation>>,
+}
+
+#[event]
+pub struct RecipientExpectationSet {
+    pub pool: Pubkey,
+    pub recipient: Pubkey,
+    pub expected_amount: u64,
+}
+
+#[event]
+pub struct RecipientExpectationClosed {
+    pub pool: Pubkey,
+    pub recipient: Pubkey,
+}
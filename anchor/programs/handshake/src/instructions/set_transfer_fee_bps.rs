@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Change the pool's transfer fee (operator only, rate limited by cooldown)
+pub fn set_transfer_fee_bps(ctx: Context<SetTransferFeeBps>, transfer_fee_bps: u16) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let clock = Clock::get()?;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    let previous_fee_bps = pool.transfer_fee_bps;
+    pool.set_transfer_fee_bps(transfer_fee_bps, clock.unix_timestamp)?;
+
+    emit!(TransferFeeUpdated {
+        pool: pool.key(),
+        previous_fee_bps,
+        transfer_fee_bps,
+        next_change_at: clock
+            .unix_timestamp
+            .checked_add(pool.effective_fee_change_cooldown())
+            .ok_or(HandshakeError::MathOverflow)?,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTransferFeeBps<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+#[event]
+pub struct TransferFeeUpdated {
+    pub pool: Pubkey,
+    pub previous_fee_bps: u16,
+    pub transfer_fee_bps: u16,
+    pub next_change_at: i64,
+}
@@ -0,0 +1,238 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+use super::{FeeAccrued, collect_sol_fee, route_fee_to_vault};
+
+/// Settle a transfer as the operator, releasing `to_recipient` to the
+/// recipient and refunding the remainder to the sender in one step. The fee
+/// is charged on the released portion only: to_sender = amount - to_recipient - fee.
+pub fn settle_transfer<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SettleTransfer<'info>>,
+    to_recipient: u64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate the operator is sufficiently staked
+    pool.validate_operator_stake()?;
+
+    // Validate transfer is active with a known amount
+    transfer.validate_active()?;
+    transfer.validate_revealed()?;
+    transfer.validate_no_milestones()?;
+    transfer.validate_single_party_release()?;
+
+    // Calculate split
+    let fee = pool.calculate_fee_for(transfer, to_recipient);
+    let to_sender = transfer
+        .amount
+        .checked_sub(to_recipient)
+        .and_then(|rest| rest.checked_sub(fee))
+        .ok_or(HandshakeError::InvalidSettlement)?;
+
+    // Stale transfers can only be force-resolved
+    transfer.validate_not_stale()?;
+
+    // Abort rather than close if the pool no longer backs this escrow
+    pool.validate_escrow(ctx.accounts.pool_token_account.amount, transfer.amount)?;
+
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    // Release the agreed portion to the recipient
+    if to_recipient > 0 {
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.recipient_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            to_recipient,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    // Refund the remainder to the sender
+    if to_sender > 0 {
+        let transfer_accounts = TransferChecked {
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.sender_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            pool_signer_seeds,
+        );
+        transfer_checked_with_hooks(
+            cpi_ctx,
+            ctx.remaining_accounts,
+            to_sender,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    // Burn the configured share of the fee, keep the rest as collected fees
+    let fee_burned = pool.calculate_fee_burn(fee);
+    if fee_burned > 0 {
+        let burn_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.pool_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let burn_cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            burn_accounts,
+            pool_signer_seeds,
+        );
+        burn(burn_cpi_ctx, fee_burned)?;
+    }
+    let fee_collected = fee
+        .checked_sub(fee_burned)
+        .ok_or(HandshakeError::CalculationError)?;
+
+    // Move the collected share into the fee vault, if the pool has one
+    route_fee_to_vault(
+        pool,
+        &ctx.accounts.pool_token_account,
+        ctx.accounts.fee_vault.as_deref(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        fee_collected,
+    )?;
+
+    // Update pool accounting; the SOL fee is only earned if something was released
+    pool.add_withdrawal(transfer.amount)?;
+    if to_recipient > 0 {
+        collect_sol_fee(pool, transfer)?;
+    }
+    if fee_collected > 0 {
+        pool.add_collected_fees(fee_collected)?;
+        emit!(FeeAccrued {
+            pool: pool.key(),
+            transfer: transfer.key(),
+            amount: fee_collected,
+            outcome: TransferStatus::Settled,
+        });
+    }
+    pool.increment_transfers_resolved(transfer.created_at)?;
+
+    // Mark transfer as settled and close (rent to sender)
+    transfer.mark_as_settled()?;
+
+    emit!(TransferSettled {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        sender: transfer.sender,
+        recipient: transfer.recipient,
+        amount: transfer.amount,
+        to_recipient,
+        to_sender,
+        fee,
+        fee_burned,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleTransfer<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation (mutable for fee burns)
+    #[account(
+        mut,
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool's token account
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program
+    )]
+    pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Recipient's token account to receive the released portion
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sender's token account to receive the remainder
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = transfer.sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Transfer account to settle (closed to sender)
+    #[account(
+        mut,
+        close = sender,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+
+    /// CHECK: Sender receives rent refund on close.
+    #[account(
+        mut,
+        constraint = transfer.sender == sender.key() @ HandshakeError::Unauthorized
+    )]
+    pub sender: AccountInfo<'info>,
+
+    /// Pool's fee vault (required when the pool has one)
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct TransferSettled {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub to_recipient: u64,
+    pub to_sender: u64,
+    pub fee: u64,
+    pub fee_burned: u64,
+}
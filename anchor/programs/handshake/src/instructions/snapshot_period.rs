@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Close the current accounting period (operator only): emit its counters
+/// and zero them. Lifetime totals are unaffected.
+pub fn snapshot_period(ctx: Context<SnapshotPeriod>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    let clock = Clock::get()?;
+
+    emit!(PeriodSnapshot {
+        pool: pool.key(),
+        period_started_at: pool.period_started_at,
+        period_ended_at: clock.unix_timestamp,
+        deposits: pool.period_deposits,
+        withdrawals: pool.period_withdrawals,
+        fees_collected: pool.period_fees_collected,
+    });
+
+    pool.reset_period(clock.unix_timestamp);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SnapshotPeriod<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+#[event]
+pub struct PeriodSnapshot {
+    pub pool: Pubkey,
+    pub period_started_at: i64,
+    pub period_ended_at: i64,
+    pub deposits: u64,
+    pub withdrawals: u64,
+    pub fees_collected: u64,
+}
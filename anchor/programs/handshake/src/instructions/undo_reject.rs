@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Undo a soft reject within its undo window, restoring the transfer to active (operator only)
+pub fn undo_reject(ctx: Context<UndoReject>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    let clock = Clock::get()?;
+    transfer.undo_reject_pending(clock.unix_timestamp)?;
+
+    emit!(TransferRejectUndone {
+        transfer: transfer.key(),
+        pool: pool.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UndoReject<'info> {
+    pub operator: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Soft-rejected transfer to restore
+    #[account(
+        mut,
+        constraint = transfer.pool == pool.key()
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+}
+
+#[event]
+pub struct TransferRejectUndone {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+}
@@ -0,0 +1,205 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Update several pool settings in one atomic call (operator only).
+/// Only fields set to `Some` are applied; each is validated as its individual setter would.
+pub fn update_pool_config(ctx: Context<UpdatePoolConfig>, params: PoolConfigParams) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let clock = Clock::get()?;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    if let Some(transfer_fee_bps) = params.transfer_fee_bps {
+        pool.set_transfer_fee_bps(transfer_fee_bps, clock.unix_timestamp)?;
+    }
+
+    if let Some(fee_burn_bps) = params.fee_burn_bps {
+        require!(fee_burn_bps <= 10000, HandshakeError::InvalidFeeConfig);
+        pool.fee_burn_bps = fee_burn_bps;
+    }
+
+    if let Some(is_paused) = params.is_paused {
+        pool.is_paused = is_paused;
+    }
+
+    if let Some(defer_missing_refunds) = params.defer_missing_refunds {
+        pool.defer_missing_refunds = defer_missing_refunds;
+    }
+
+    if let Some(allow_transfer_hooks) = params.allow_transfer_hooks {
+        pool.allow_transfer_hooks = allow_transfer_hooks;
+    }
+
+    if let Some(expiry_behavior) = params.expiry_behavior {
+        require!(
+            expiry_behavior == EXPIRY_REFUND_SENDER || expiry_behavior == EXPIRY_FORWARD_RECIPIENT,
+            HandshakeError::InvalidExpiryBehavior
+        );
+        pool.expiry_behavior = expiry_behavior;
+    }
+
+    if let Some(event_verbosity) = params.event_verbosity {
+        require!(
+            event_verbosity == EVENT_VERBOSITY_MINIMAL || event_verbosity == EVENT_VERBOSITY_FULL,
+            HandshakeError::InvalidEventVerbosity
+        );
+        pool.event_verbosity = event_verbosity;
+    }
+
+    // The lifetime and stale fee are locked onto each transfer at creation, so
+    // changing them here only affects transfers created afterwards
+    if let Some(max_lifetime_seconds) = params.max_lifetime_seconds {
+        require!(max_lifetime_seconds >= 0, HandshakeError::InvalidTimeWindow);
+        pool.max_lifetime_seconds = max_lifetime_seconds;
+    }
+
+    if let Some(stale_fee_bps) = params.stale_fee_bps {
+        require!(
+            stale_fee_bps <= 10000 - MIN_REFUND_BPS,
+            HandshakeError::InvalidFeeConfig
+        );
+        pool.stale_fee_bps = stale_fee_bps;
+    }
+
+    if let Some(reject_undo_window) = params.reject_undo_window {
+        require!(reject_undo_window >= 0, HandshakeError::InvalidTimeWindow);
+        pool.reject_undo_window = reject_undo_window;
+    }
+
+    if let Some(coupon_signer) = params.coupon_signer {
+        pool.coupon_signer = coupon_signer;
+    }
+
+    if let Some(allow_self_transfer) = params.allow_self_transfer {
+        pool.allow_self_transfer = allow_self_transfer;
+    }
+
+    if let Some(min_operator_stake) = params.min_operator_stake {
+        // Lowering the minimum while transfers are open would free the stake backing them
+        require!(
+            min_operator_stake >= pool.min_operator_stake || !pool.has_outstanding_transfers(),
+            HandshakeError::OutstandingTransfers
+        );
+        pool.min_operator_stake = min_operator_stake;
+    }
+
+    if let Some(notify_memo) = params.notify_memo {
+        pool.notify_memo = notify_memo;
+    }
+
+    if params.fee_in_sol.is_some() || params.sol_fee_lamports.is_some() {
+        pool.set_sol_fee(params.fee_in_sol, params.sol_fee_lamports, clock.unix_timestamp)?;
+    }
+
+    // Applied after the fee changes above, so a shorter cooldown can't clear them early
+    if let Some(fee_change_cooldown) = params.fee_change_cooldown {
+        pool.set_fee_change_cooldown(fee_change_cooldown)?;
+    }
+
+    if let Some(free_transfer_count) = params.free_transfer_count {
+        pool.free_transfer_count = free_transfer_count;
+    }
+
+    if let Some(max_extension_seconds) = params.max_extension_seconds {
+        require!(max_extension_seconds >= 0, HandshakeError::InvalidTimeWindow);
+        pool.max_extension_seconds = max_extension_seconds;
+    }
+
+    emit!(PoolConfigUpdated {
+        pool: pool.key(),
+        transfer_fee_bps: params.transfer_fee_bps,
+        fee_burn_bps: params.fee_burn_bps,
+        is_paused: params.is_paused,
+        defer_missing_refunds: params.defer_missing_refunds,
+        allow_transfer_hooks: params.allow_transfer_hooks,
+        expiry_behavior: params.expiry_behavior,
+        event_verbosity: params.event_verbosity,
+        max_lifetime_seconds: params.max_lifetime_seconds,
+        stale_fee_bps: params.stale_fee_bps,
+        reject_undo_window: params.reject_undo_window,
+        coupon_signer: params.coupon_signer,
+        allow_self_transfer: params.allow_self_transfer,
+        min_operator_stake: params.min_operator_stake,
+        notify_memo: params.notify_memo,
+        max_extension_seconds: params.max_extension_seconds,
+        fee_in_sol: params.fee_in_sol,
+        sol_fee_lamports: params.sol_fee_lamports,
+        free_transfer_count: params.free_transfer_count,
+        fee_change_cooldown: params.fee_change_cooldown,
+    });
+
+    Ok(())
+}
+
+/// Pool settings to change; `None` leaves a field as is
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct PoolConfigParams {
+    pub transfer_fee_bps: Option<u16>,
+    pub fee_burn_bps: Option<u16>,
+    pub is_paused: Option<bool>,
+    pub defer_missing_refunds: Option<bool>,
+    pub allow_transfer_hooks: Option<bool>,
+    pub expiry_behavior: Option<u8>,
+    pub event_verbosity: Option<u8>,
+    /// Applies to transfers created after the update
+    pub max_lifetime_seconds: Option<i64>,
+    /// Applies to transfers created after the update
+    pub stale_fee_bps: Option<u16>,
+    pub reject_undo_window: Option<i64>,
+    /// `Pubkey::default()` disables coupons
+    pub coupon_signer: Option<Pubkey>,
+    pub allow_self_transfer: Option<bool>,
+    pub min_operator_stake: Option<u64>,
+    pub notify_memo: Option<bool>,
+    pub max_extension_seconds: Option<i64>,
+    pub fee_in_sol: Option<bool>,
+    pub sol_fee_lamports: Option<u64>,
+    pub free_transfer_count: Option<u32>,
+    /// Seconds between fee changes, MIN_FEE_CHANGE_COOLDOWN to MAX_FEE_CHANGE_COOLDOWN
+    pub fee_change_cooldown: Option<i64>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+/// Event listing the fields changed by `update_pool_config` (`None` = unchanged)
+#[event]
+pub struct PoolConfigUpdated {
+    pub pool: Pubkey,
+    pub transfer_fee_bps: Option<u16>,
+    pub fee_burn_bps: Option<u16>,
+    pub is_paused: Option<bool>,
+    pub defer_missing_refunds: Option<bool>,
+    pub allow_transfer_hooks: Option<bool>,
+    pub expiry_behavior: Option<u8>,
+    pub event_verbosity: Option<u8>,
+    pub max_lifetime_seconds: Option<i64>,
+    pub stale_fee_bps: Option<u16>,
+    pub reject_undo_window: Option<i64>,
+    pub coupon_signer: Option<Pubkey>,
+    pub allow_self_transfer: Option<bool>,
+    pub min_operator_stake: Option<u64>,
+    pub notify_memo: Option<bool>,
+    pub max_extension_seconds: Option<i64>,
+    pub fee_in_sol: Option<bool>,
+    pub sol_fee_lamports: Option<u64>,
+    pub free_transfer_count: Option<u32>,
+    pub fee_change_cooldown: Option<i64>,
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Set the pool's display name and metadata URI (operator only). Creates the
+/// metadata account for pools initialized before it existed.
+pub fn update_pool_metadata(
+    ctx: Context<UpdatePoolMetadata>,
+    name: String,
+    uri: String,
+) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    let pool_metadata = &mut ctx.accounts.pool_metadata;
+    pool_metadata.version = 1;
+    pool_metadata.bump = ctx.bumps.pool_metadata;
+    pool_metadata.pool = pool.key();
+    pool_metadata.set(name, uri)?;
+
+    emit!(PoolMetadataUpdated {
+        pool: pool.key(),
+        name: pool_metadata.name.clone(),
+        uri: pool_metadata.uri.clone(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolMetadata<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool metadata - PDA derived from the pool
+    #[account(
+        init_if_needed,
+        payer = operator,
+        space = PoolMetadata::SPACE,
+        seeds = [
+            POOL_METADATA_SEED,
+            pool.key().as_ref()
+        ],
+        bump
+    )]
+    pub pool_metadata: Box<Account<'info, PoolMetadata>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct PoolMetadataUpdated {
+    pub pool: Pubkey,
+    pub name: String,
+    pub uri: String,
+}
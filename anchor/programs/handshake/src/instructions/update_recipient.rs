@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Redirect an active transfer to a new recipient (current recipient and
+/// operator both sign), e.g. when the recipient has lost access to their
+/// wallet. The transfer keeps its address, which still derives from the
+/// original recipient.
+pub fn update_recipient(ctx: Context<UpdateRecipient>, new_recipient: Pubkey) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let transfer = &mut ctx.accounts.transfer;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    // Validate transfer is active
+    transfer.validate_active()?;
+
+    // The operator must not be able to redirect funds to itself; no-op updates are rejected
+    require!(
+        new_recipient != pool.operator && new_recipient != transfer.recipient,
+        HandshakeError::InvalidRecipient
+    );
+    require!(
+        pool.allow_self_transfer || new_recipient != transfer.sender,
+        HandshakeError::SelfTransfer
+    );
+
+    let old_recipient = transfer.recipient;
+    transfer.recipient = new_recipient;
+
+    emit!(RecipientUpdated {
+        transfer: transfer.key(),
+        pool: pool.key(),
+        old_recipient,
+        new_recipient,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateRecipient<'info> {
+    pub recipient: Signer<'info>,
+
+    pub operator: Signer<'info>,
+
+    /// The pool this transfer belongs to
+    #[account(
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Transfer to redirect
+    #[account(
+        mut,
+        constraint = transfer.pool == pool.key(),
+        constraint = transfer.recipient == recipient.key() @ HandshakeError::Unauthorized
+    )]
+    pub transfer: Box<Account<'info, SecureTransfer>>,
+}
+
+#[event]
+pub struct RecipientUpdated {
+    pub transfer: Pubkey,
+    pub pool: Pubkey,
+    pub old_recipient: Pubkey,
+    pub new_recipient: Pubkey,
+}
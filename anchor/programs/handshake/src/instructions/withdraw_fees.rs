@@ -1,9 +1,17 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface},
+};
 use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
 
-/// Withdraw collected fees (operator only)
-pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
+/// Withdraw collected fees (operator only) to the pool's registered fee
+/// account, or to the operator's ATA if none is registered. Fees are pulled
+/// from the pool's fee vault when it has one.
+pub fn withdraw_fees<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, WithdrawFees<'info>>,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
 
     // Validate operator
@@ -12,15 +20,44 @@ pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
         HandshakeError::Unauthorized
     );
 
+    // Validate the destination is the registered fee account
+    let expected_fee_account = if pool.operator_fee_account != Pubkey::default() {
+        pool.operator_fee_account
+    } else {
+        get_associated_token_address_with_program_id(
+            &pool.operator,
+            &pool.mint,
+            &ctx.accounts.token_program.key(),
+        )
+    };
+    require_keys_eq!(
+        ctx.accounts.operator_token_account.key(),
+        expected_fee_account,
+        HandshakeError::InvalidFeeAccount
+    );
+
     let fees = pool.collected_fees;
     require!(fees > 0, HandshakeError::CalculationError);
 
+    // Fees sit in the fee vault if the pool routes them there
+    let fee_source = if pool.fee_vault != Pubkey::default() {
+        let fee_vault = ctx
+            .accounts
+            .fee_vault
+            .as_ref()
+            .ok_or(HandshakeError::FeeVaultRequired)?;
+        require_keys_eq!(fee_vault.key(), pool.fee_vault, HandshakeError::FeeVaultRequired);
+        fee_vault.to_account_info()
+    } else {
+        ctx.accounts.pool_token_account.to_account_info()
+    };
+
     // Transfer fees to operator
     let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
     let pool_signer_seeds = &[&pool_seeds[..]];
 
     let transfer_accounts = TransferChecked {
-        from: ctx.accounts.pool_token_account.to_account_info(),
+        from: fee_source,
         mint: ctx.accounts.mint.to_account_info(),
         to: ctx.accounts.operator_token_account.to_account_info(),
         authority: pool.to_account_info(),
@@ -30,7 +67,12 @@ pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
         transfer_accounts,
         pool_signer_seeds,
     );
-    transfer_checked(cpi_ctx, fees, ctx.accounts.mint.decimals)?;
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        fees,
+        ctx.accounts.mint.decimals,
+    )?;
 
     // Reset collected fees
     pool.reset_collected_fees();
@@ -75,15 +117,18 @@ pub struct WithdrawFees<'info> {
     )]
     pub pool_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Operator's token account to receive fees
+    /// Token account to receive fees: the registered fee account, else the operator's ATA
     #[account(
         mut,
-        associated_token::mint = pool.mint,
-        associated_token::authority = operator,
-        associated_token::token_program = token_program
+        token::mint = pool.mint,
+        token::token_program = token_program
     )]
     pub operator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Pool's fee vault (required when the pool has one)
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
 }
 
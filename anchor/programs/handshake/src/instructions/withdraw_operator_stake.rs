@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TransferChecked, Mint, TokenAccount, TokenInterface};
+use crate::{state::*, errors::*, constants::*};
+use crate::transfer_hook::transfer_checked_with_hooks;
+use super::OperatorStakeChanged;
+
+/// Withdraw operator stake (operator only). While transfers are outstanding
+/// the remaining stake must still cover the pool minimum.
+pub fn withdraw_operator_stake<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, WithdrawOperatorStake<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    let remaining = pool
+        .operator_stake
+        .checked_sub(amount)
+        .ok_or(HandshakeError::InsufficientFunds)?;
+    require!(
+        !pool.has_outstanding_transfers() || remaining >= pool.min_operator_stake,
+        HandshakeError::InsufficientOperatorStake
+    );
+
+    let pool_seeds = &[POOL_SEED, pool.pool_id.as_ref(), &[pool.bump]];
+    let pool_signer_seeds = &[&pool_seeds[..]];
+
+    let transfer_accounts = TransferChecked {
+        from: ctx.accounts.stake_vault.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.operator_token_account.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_accounts,
+        pool_signer_seeds,
+    );
+    transfer_checked_with_hooks(
+        cpi_ctx,
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    pool.operator_stake = remaining;
+
+    emit!(OperatorStakeChanged {
+        pool: pool.key(),
+        operator_stake: pool.operator_stake,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawOperatorStake<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The mint for validation
+    #[account(
+        constraint = mint.key() == pool.mint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token account receiving the stake
+    #[account(
+        mut,
+        token::mint = pool.mint,
+        token::token_program = token_program
+    )]
+    pub operator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool's stake vault
+    #[account(
+        mut,
+        seeds = [
+            STAKE_SEED,
+            pool.key().as_ref()
+        ],
+        bump = pool.stake_vault_bump
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
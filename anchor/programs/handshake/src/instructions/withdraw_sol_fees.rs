@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::{state::*, errors::*, constants::*};
+
+/// Withdraw the SOL fees held in the pool account (operator only)
+pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    // Validate operator
+    require!(
+        ctx.accounts.operator.key() == pool.operator,
+        HandshakeError::Unauthorized
+    );
+
+    let amount = pool.collected_sol_fees;
+    require!(amount > 0, HandshakeError::CalculationError);
+
+    // The pool is program-owned, so lamports above its rent are moved directly
+    let pool_info = pool.to_account_info();
+    let operator_info = ctx.accounts.operator.to_account_info();
+    **pool_info.try_borrow_mut_lamports()? = pool_info
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(HandshakeError::InsufficientFunds)?;
+    **operator_info.try_borrow_mut_lamports()? = operator_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(HandshakeError::MathOverflow)?;
+
+    pool.collected_sol_fees = 0;
+
+    emit!(SolFeesWithdrawn {
+        pool: pool.key(),
+        operator: ctx.accounts.operator.key(),
+        lamports: amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSolFees<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED,
+            pool.pool_id.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+#[event]
+pub struct SolFeesWithdrawn {
+    pub pool: Pubkey,
+    pub operator: Pubkey,
+    pub lamports: u64,
+}
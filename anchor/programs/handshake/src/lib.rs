@@ -1,9 +1,13 @@
 use anchor_lang::prelude::*;
 
+#[cfg(feature = "client")]
+pub mod client;
 mod constants;
 mod errors;
 mod instructions;
+mod signature;
 mod state;
+mod transfer_hook;
 
 use instructions::*;
 use state::*;
@@ -18,10 +22,23 @@ pub mod handshake {
         ctx: Context<InitPool>,
         pool_id: Pubkey,
         transfer_fee_bps: u16,
+        name: String,
+        uri: String,
     ) -> Result<()> {
-        instructions::init_pool(ctx, pool_id, transfer_fee_bps)
+        instructions::init_pool(ctx, pool_id, transfer_fee_bps, name, uri)
     }
 
+    pub fn init_canonical_pool(
+        ctx: Context<InitCanonicalPool>,
+        pool_id: Pubkey,
+        transfer_fee_bps: u16,
+        name: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::init_canonical_pool(ctx, pool_id, transfer_fee_bps, name, uri)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_transfer<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, CreateTransfer<'info>>,
         recipient: Pubkey,
@@ -30,6 +47,13 @@ pub mod handshake {
         memo: String,
         claimable_after: i64,
         claimable_until: i64,
+        claim_code_hash: Option<[u8; 32]>,
+        keeper_tip: u64,
+        refund_token_account: Option<Pubkey>,
+        requires_mutual: bool,
+        coupon: Option<FeeCoupon>,
+        exact_amount: Option<u64>,
+        max_sol_fee_lamports: u64,
     ) -> Result<()> {
         instructions::create_transfer(
             ctx,
@@ -39,13 +63,61 @@ pub mod handshake {
             memo,
             claimable_after,
             claimable_until,
+            claim_code_hash,
+            keeper_tip,
+            refund_token_account,
+            requires_mutual,
+            coupon,
+            exact_amount,
+            max_sol_fee_lamports,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_transfer_committed<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CreateTransfer<'info>>,
+        recipient: Pubkey,
+        nonce: u64,
+        max_amount: u64,
+        amount_commitment: [u8; 32],
+        memo: String,
+        claimable_after: i64,
+        claimable_until: i64,
+        max_sol_fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::create_transfer_committed(
+            ctx,
+            recipient,
+            nonce,
+            max_amount,
+            amount_commitment,
+            memo,
+            claimable_after,
+            claimable_until,
+            max_sol_fee_lamports,
         )
     }
 
+    pub fn reveal_transfer<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RevealTransfer<'info>>,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_transfer(ctx, amount, salt)
+    }
+
     pub fn claim_transfer<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, ClaimTransfer<'info>>,
+        claim_code: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_transfer(ctx, claim_code)
+    }
+
+    pub fn mutual_accept<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, MutualAccept<'info>>,
+        claim_code: Option<[u8; 32]>,
     ) -> Result<()> {
-        instructions::claim_transfer(ctx)
+        instructions::mutual_accept(ctx, claim_code)
     }
 
     pub fn cancel_transfer<'a, 'b, 'c, 'info>(
@@ -61,6 +133,153 @@ pub mod handshake {
         instructions::reject_transfer(ctx, reason)
     }
 
+    pub fn undo_reject(ctx: Context<UndoReject>) -> Result<()> {
+        instructions::undo_reject(ctx)
+    }
+
+    pub fn finalize_reject<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, FinalizeReject<'info>>,
+    ) -> Result<()> {
+        instructions::finalize_reject(ctx)
+    }
+
+    pub fn reject_transfer_by_signature<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RejectTransferBySignature<'info>>,
+        reason: Option<u8>,
+        operator_nonce: u64,
+    ) -> Result<()> {
+        instructions::reject_transfer_by_signature(ctx, reason, operator_nonce)
+    }
+
+    pub fn set_defer_missing_refunds(
+        ctx: Context<SetDeferMissingRefunds>,
+        defer_missing_refunds: bool,
+    ) -> Result<()> {
+        instructions::set_defer_missing_refunds(ctx, defer_missing_refunds)
+    }
+
+    pub fn set_operator_fee_account(ctx: Context<SetOperatorFeeAccount>) -> Result<()> {
+        instructions::set_operator_fee_account(ctx)
+    }
+
+    pub fn snapshot_period(ctx: Context<SnapshotPeriod>) -> Result<()> {
+        instructions::snapshot_period(ctx)
+    }
+
+    pub fn init_stake_vault(ctx: Context<InitStakeVault>) -> Result<()> {
+        instructions::init_stake_vault(ctx)
+    }
+
+    pub fn deposit_operator_stake<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DepositOperatorStake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_operator_stake(ctx, amount)
+    }
+
+    pub fn withdraw_operator_stake<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, WithdrawOperatorStake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_operator_stake(ctx, amount)
+    }
+
+    pub fn set_fee_override(ctx: Context<SetFeeOverride>, fee_override_bps: Option<u16>) -> Result<()> {
+        instructions::set_fee_override(ctx, fee_override_bps)
+    }
+
+    pub fn set_milestones(ctx: Context<SetMilestones>, milestones_bps: Vec<u16>) -> Result<()> {
+        instructions::set_milestones(ctx, milestones_bps)
+    }
+
+    pub fn release_milestone<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ReleaseMilestone<'info>>,
+        index: u8,
+    ) -> Result<()> {
+        instructions::release_milestone(ctx, index)
+    }
+
+    pub fn init_fee_vault<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, InitFeeVault<'info>>,
+    ) -> Result<()> {
+        instructions::init_fee_vault(ctx)
+    }
+
+    pub fn reject_all_from_sender<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RejectAllFromSender<'info>>,
+        reason: Option<u8>,
+    ) -> Result<()> {
+        instructions::reject_all_from_sender(ctx, reason)
+    }
+
+    pub fn extend_transfer(ctx: Context<ExtendTransfer>, extension_seconds: i64) -> Result<()> {
+        instructions::extend_transfer(ctx, extension_seconds)
+    }
+
+    pub fn quote_net_received(ctx: Context<QuoteNetReceived>, amount: u64) -> Result<NetQuote> {
+        instructions::quote_net_received(ctx, amount)
+    }
+
+    pub fn update_recipient(ctx: Context<UpdateRecipient>, new_recipient: Pubkey) -> Result<()> {
+        instructions::update_recipient(ctx, new_recipient)
+    }
+
+    pub fn propose_reconciliation(
+        ctx: Context<ProposeReconciliation>,
+        active_escrow_total: u64,
+    ) -> Result<()> {
+        instructions::propose_reconciliation(ctx, active_escrow_total)
+    }
+
+    pub fn reconcile_accounting(ctx: Context<ReconcileAccounting>) -> Result<()> {
+        instructions::reconcile_accounting(ctx)
+    }
+
+    pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>) -> Result<()> {
+        instructions::withdraw_sol_fees(ctx)
+    }
+
+    pub fn update_pool_metadata(
+        ctx: Context<UpdatePoolMetadata>,
+        name: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::update_pool_metadata(ctx, name, uri)
+    }
+
+    pub fn reject_transfer_split_refund<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RejectTransferSplitRefund<'info>>,
+        reason: Option<u8>,
+        splits: Vec<RefundSplit>,
+    ) -> Result<()> {
+        instructions::reject_transfer_split_refund(ctx, reason, splits)
+    }
+
+    pub fn init_sender_profile(ctx: Context<InitSenderProfile>) -> Result<()> {
+        instructions::init_sender_profile(ctx)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool(ctx)
+    }
+
+    pub fn migrate_transfer(ctx: Context<MigrateTransfer>) -> Result<()> {
+        instructions::migrate_transfer(ctx)
+    }
+
+    pub fn claim_refund<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClaimRefund<'info>>,
+    ) -> Result<()> {
+        instructions::claim_refund(ctx)
+    }
+
+    pub fn settle_transfer<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SettleTransfer<'info>>,
+        to_recipient: u64,
+    ) -> Result<()> {
+        instructions::settle_transfer(ctx, to_recipient)
+    }
+
     pub fn decline_transfer<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, DeclineTransfer<'info>>,
         reason: Option<u8>,
@@ -74,11 +293,21 @@ pub mod handshake {
         instructions::expire_transfer(ctx)
     }
 
-    pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
+    pub fn force_resolve<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ForceResolve<'info>>,
+    ) -> Result<()> {
+        instructions::force_resolve(ctx)
+    }
+
+    pub fn withdraw_fees<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, WithdrawFees<'info>>,
+    ) -> Result<()> {
         instructions::withdraw_fees(ctx)
     }
 
-    pub fn destroy_transfer(ctx: Context<DestroyTransfer>) -> Result<()> {
+    pub fn destroy_transfer<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DestroyTransfer<'info>>,
+    ) -> Result<()> {
         instructions::destroy_transfer(ctx)
     }
 
@@ -90,7 +319,49 @@ pub mod handshake {
         instructions::reset_pool(ctx)
     }
 
-    pub fn close_pool(ctx: Context<ClosePool>, withdrawal_amount: u64) -> Result<()> {
+    pub fn close_pool<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClosePool<'info>>,
+        withdrawal_amount: u64,
+    ) -> Result<()> {
         instructions::close_pool(ctx, withdrawal_amount)
     }
+
+    pub fn set_fee_burn_bps(ctx: Context<SetFeeBurnBps>, fee_burn_bps: u16) -> Result<()> {
+        instructions::set_fee_burn_bps(ctx, fee_burn_bps)
+    }
+
+    pub fn set_transfer_fee_bps(
+        ctx: Context<SetTransferFeeBps>,
+        transfer_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::set_transfer_fee_bps(ctx, transfer_fee_bps)
+    }
+
+    pub fn batch_withdraw_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchWithdrawFees<'info>>,
+    ) -> Result<()> {
+        instructions::batch_withdraw_fees(ctx)
+    }
+
+    pub fn set_recipient_expectation(
+        ctx: Context<SetRecipientExpectation>,
+        expected_amount: u64,
+    ) -> Result<()> {
+        instructions::set_recipient_expectation(ctx, expected_amount)
+    }
+
+    pub fn close_recipient_expectation(ctx: Context<CloseRecipientExpectation>) -> Result<()> {
+        instructions::close_recipient_expectation(ctx)
+    }
+
+    pub fn update_pool_config(
+        ctx: Context<UpdatePoolConfig>,
+        params: PoolConfigParams,
+    ) -> Result<()> {
+        instructions::update_pool_config(ctx, params)
+    }
+
+    pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStats> {
+        instructions::get_pool_stats(ctx)
+    }
 }
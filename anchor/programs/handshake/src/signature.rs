@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_sdk_ids::ed25519_program;
+use crate::errors::HandshakeError;
+
+// Ed25519 precompile instruction layout (single signature)
+const ED25519_HEADER_LEN: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+// Offsets pointing into the Ed25519 instruction's own data
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Domain prefix for operator-signed reject authorizations
+pub const REJECT_MESSAGE_DOMAIN: &[u8] = b"handshake:reject_transfer";
+
+/// Domain prefix for pool fee coupons
+pub const COUPON_MESSAGE_DOMAIN: &[u8] = b"handshake:fee_coupon";
+
+/// Off-chain message a pool's coupon signer signs to grant `sender` a fee discount
+pub fn coupon_message(pool: &Pubkey, sender: &Pubkey, discount_bps: u16, expiry: i64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(COUPON_MESSAGE_DOMAIN.len() + 32 + 32 + 2 + 8);
+    message.extend_from_slice(COUPON_MESSAGE_DOMAIN);
+    message.extend_from_slice(pool.as_ref());
+    message.extend_from_slice(sender.as_ref());
+    message.extend_from_slice(&discount_bps.to_le_bytes());
+    message.extend_from_slice(&expiry.to_le_bytes());
+    message
+}
+
+/// Off-chain message an operator signs to authorize rejecting `transfer`
+pub fn reject_message(transfer: &Pubkey, reason: Option<u8>, operator_nonce: u64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(REJECT_MESSAGE_DOMAIN.len() + 32 + 8 + 2);
+    message.extend_from_slice(REJECT_MESSAGE_DOMAIN);
+    message.extend_from_slice(transfer.as_ref());
+    message.extend_from_slice(&operator_nonce.to_le_bytes());
+    match reason {
+        Some(code) => message.extend_from_slice(&[1, code]),
+        None => message.push(0),
+    }
+    message
+}
+
+/// Verify that the instruction immediately before the current one is an
+/// Ed25519 precompile check of `message` signed by `signer`
+pub fn verify_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, HandshakeError::InvalidSignature);
+
+    let ix = load_instruction_at_checked((current_index - 1) as usize, instructions_sysvar)?;
+    require!(
+        ix.program_id == ed25519_program::ID && ix.accounts.is_empty(),
+        HandshakeError::InvalidSignature
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LEN + ED25519_OFFSETS_LEN && data[0] == 1,
+        HandshakeError::InvalidSignature
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = ED25519_HEADER_LEN;
+    let signature_ix = read_u16(offsets + 2);
+    let public_key_offset = read_u16(offsets + 4) as usize;
+    let public_key_ix = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_size = read_u16(offsets + 10) as usize;
+    let message_ix = read_u16(offsets + 12);
+
+    // Signature, key and message must all live in the precompile instruction
+    require!(
+        signature_ix == CURRENT_INSTRUCTION
+            && public_key_ix == CURRENT_INSTRUCTION
+            && message_ix == CURRENT_INSTRUCTION,
+        HandshakeError::InvalidSignature
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(HandshakeError::InvalidSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(HandshakeError::InvalidSignature)?;
+
+    require!(
+        public_key == signer.as_ref() && signed_message == message,
+        HandshakeError::InvalidSignature
+    );
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+/// Marks the single canonical pool an operator runs for a mint
+#[account]
+pub struct CanonicalPool {
+    /// Version for upgrades
+    pub version: u8,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Operator the canonical pool belongs to
+    pub operator: Pubkey,
+
+    /// Token mint of the canonical pool
+    pub mint: Pubkey,
+
+    /// The canonical pool account
+    pub pool: Pubkey,
+
+    /// Padding for future upgrades
+    pub _padding: [u8; 32],
+}
+
+impl CanonicalPool {
+    pub const SPACE: usize = 8 + // discriminator
+        1 + // version
+        1 + // bump
+        32 + // operator
+        32 + // mint
+        32 + // pool
+        32; // _padding
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+/// Record of a sender redeeming a pool's fee coupon (one per sender per pool)
+#[account]
+pub struct CouponRedemption {
+    /// Version for upgrades
+    pub version: u8,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Pool the coupon was issued for
+    pub pool: Pubkey,
+
+    /// Sender who redeemed it
+    pub sender: Pubkey,
+
+    /// Transfer the discount was applied to
+    pub transfer: Pubkey,
+
+    /// Discount granted (bps of the pool fee)
+    pub discount_bps: u16,
+
+    /// When the coupon was redeemed (unix timestamp)
+    pub redeemed_at: i64,
+
+    /// Padding for future upgrades
+    pub _padding: [u8; 32],
+}
+
+impl CouponRedemption {
+    pub const SPACE: usize = 8 + // discriminator
+        1 + // version
+        1 + // bump
+        32 + // pool
+        32 + // sender
+        32 + // transfer
+        2 + // discount_bps
+        8 + // redeemed_at
+        32; // _padding
+}
@@ -1,5 +1,19 @@
 mod pool;
 mod secure_transfer;
+mod recipient_expectation;
+mod canonical_pool;
+mod pending_refund;
+mod coupon_redemption;
+mod reconciliation_proposal;
+mod pool_metadata;
+mod sender_profile;
 
 pub use pool::*;
 pub use secure_transfer::*;
+pub use recipient_expectation::*;
+pub use canonical_pool::*;
+pub use pending_refund::*;
+pub use coupon_redemption::*;
+pub use reconciliation_proposal::*;
+pub use pool_metadata::*;
+pub use sender_profile::*;
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+/// Refund owed to a sender whose token account was missing at rejection
+#[account]
+pub struct PendingRefund {
+    /// Version for upgrades
+    pub version: u8,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Pool holding the refunded tokens
+    pub pool: Pubkey,
+
+    /// The rejected transfer this refund came from
+    pub transfer: Pubkey,
+
+    /// Sender owed the refund
+    pub sender: Pubkey,
+
+    /// Token amount owed
+    pub amount: u64,
+
+    /// When the refund was deferred (unix timestamp)
+    pub created_at: i64,
+
+    /// Padding for future upgrades
+    pub _padding: [u8; 32],
+}
+
+impl PendingRefund {
+    pub const SPACE: usize = 8 + // discriminator
+        1 + // version
+        1 + // bump
+        32 + // pool
+        32 + // transfer
+        32 + // sender
+        8 + // amount
+        8 + // created_at
+        32; // _padding
+}
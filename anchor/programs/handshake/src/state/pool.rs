@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
+use crate::constants::{
+    DEFAULT_FEE_CHANGE_COOLDOWN, EVENT_VERBOSITY_FULL, EXPIRY_REFUND_SENDER, MAX_FEE_CHANGE_COOLDOWN,
+    MAX_SOL_FEE_LAMPORTS, MIN_FEE_CHANGE_COOLDOWN, MIN_REFUND_BPS, POOL_VERSION,
+};
 use crate::errors::HandshakeError;
+use super::SecureTransfer;
 
 #[account]
 pub struct Pool {
@@ -40,8 +45,96 @@ pub struct Pool {
     /// Emergency controls
     pub is_paused: bool,
 
+    /// Share of each collected fee that is burned, in basis points (0-10000)
+    pub fee_burn_bps: u16,
+
+    /// Last time the transfer fee changed (0 = never, so the first change is immediate)
+    pub last_fee_change_at: i64,
+
+    /// Minimum seconds between transfer fee changes (0 = DEFAULT_FEE_CHANGE_COOLDOWN)
+    pub fee_change_cooldown: i64,
+
+    /// Next nonce expected on an operator resolution submitted by a relayer
+    pub operator_nonce: u64,
+
+    /// Record a claimable refund instead of failing when a reject can't deliver tokens
+    pub defer_missing_refunds: bool,
+
+    /// Deferred refunds not yet claimed
+    pub pending_refunds: u64,
+
+    /// Sum of (resolved_at - created_at) over resolved transfers, in seconds
+    pub total_resolution_time: u128,
+
+    /// Accept Token-2022 mints with a TransferHook (hook accounts passed as remaining accounts)
+    pub allow_transfer_hooks: bool,
+
+    /// What expire_transfer does with expired transfers (EXPIRY_* constants)
+    pub expiry_behavior: u8,
+
+    /// How much detail resolution events carry (EVENT_VERBOSITY_* constants)
+    pub event_verbosity: u8,
+
+    /// Age after which a transfer can only be force-resolved (0 = no limit);
+    /// locked onto each transfer at creation
+    pub max_lifetime_seconds: i64,
+
+    /// Fee (bps) kept from force-resolved transfers; locked onto each transfer at creation
+    pub stale_fee_bps: u16,
+
+    /// Seconds a reject can be undone before it refunds (0 = rejects refund immediately)
+    pub reject_undo_window: i64,
+
+    /// Key whose Ed25519 fee coupons senders can redeem (default = coupons disabled)
+    pub coupon_signer: Pubkey,
+
+    /// Token account fee withdrawals must go to (default = the operator's ATA)
+    pub operator_fee_account: Pubkey,
+
+    /// Whether senders may create transfers to themselves
+    pub allow_self_transfer: bool,
+
+    /// Accounting period counters, zeroed by snapshot_period
+    pub period_deposits: u64,
+    pub period_withdrawals: u64,
+    pub period_fees_collected: u64,
+    pub period_started_at: i64,
+
+    /// Stake the operator must keep in the stake vault to resolve transfers
+    pub min_operator_stake: u64,
+
+    /// Tokens the operator holds in the pool's stake vault
+    pub operator_stake: u64,
+
+    /// Token account collected fees are routed to (default = fees stay in the pool token account)
+    pub fee_vault: Pubkey,
+
+    /// Whether recipient payouts carry an SPL Memo with the transfer memo
+    pub notify_memo: bool,
+
+    /// Total seconds a transfer's claim deadline may be extended by (0 = no extensions)
+    pub max_extension_seconds: i64,
+
+    /// Whether the transfer fee is charged in SOL at creation instead of in tokens at release
+    pub fee_in_sol: bool,
+
+    /// SOL fee charged per transfer when fee_in_sol is set (at most MAX_SOL_FEE_LAMPORTS)
+    pub sol_fee_lamports: u64,
+
+    /// SOL fees held in the pool account, withdrawable by the operator
+    pub collected_sol_fees: u64,
+
+    /// Number of a sender's first resolved transfers that are charged no fee
+    pub free_transfer_count: u32,
+
+    /// Cached bump of the stake vault PDA, recorded by init_stake_vault (0 = no vault)
+    pub stake_vault_bump: u8,
+
+    /// Last time fee_in_sol or sol_fee_lamports changed (0 = never, so the first setup is immediate)
+    pub last_sol_fee_change_at: i64,
+
     /// Padding for future upgrades
-    pub _padding: [u8; 128],
+    pub _padding: [u8; 16],
 }
 
 impl Pool {
@@ -59,20 +152,251 @@ impl Pool {
         8 + // total_transfers_resolved
         8 + // collected_fees
         1 + // is_paused
-        128; // _padding
+        2 + // fee_burn_bps
+        8 + // last_fee_change_at
+        8 + // fee_change_cooldown
+        8 + // operator_nonce
+        1 + // defer_missing_refunds
+        8 + // pending_refunds
+        16 + // total_resolution_time
+        1 + // allow_transfer_hooks
+        1 + // expiry_behavior
+        1 + // event_verbosity
+        8 + // max_lifetime_seconds
+        2 + // stale_fee_bps
+        8 + // reject_undo_window
+        32 + // coupon_signer
+        32 + // operator_fee_account
+        1 + // allow_self_transfer
+        8 + // period_deposits
+        8 + // period_withdrawals
+        8 + // period_fees_collected
+        8 + // period_started_at
+        8 + // min_operator_stake
+        8 + // operator_stake
+        32 + // fee_vault
+        1 + // notify_memo
+        8 + // max_extension_seconds
+        1 + // fee_in_sol
+        8 + // sol_fee_lamports
+        8 + // collected_sol_fees
+        4 + // free_transfer_count
+        1 + // stake_vault_bump
+        8 + // last_sol_fee_change_at
+        16; // _padding
 
-    /// Calculate transfer fee amount
+    /// Initialize a new pool
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        pool_id: Pubkey,
+        operator: Pubkey,
+        mint: Pubkey,
+        transfer_fee_bps: u16,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+
+        // Validate fee configuration
+        require!(
+            transfer_fee_bps <= 10000,
+            HandshakeError::InvalidTransferFee
+        );
+
+        self.version = POOL_VERSION;
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.operator = operator;
+        self.mint = mint;
+        self.transfer_fee_bps = transfer_fee_bps;
+
+        // Initialize tracking
+        self.total_deposits = 0;
+        self.total_withdrawals = 0;
+        self.total_escrowed = 0;
+        self.total_transfers_created = 0;
+        self.total_transfers_resolved = 0;
+        self.collected_fees = 0;
+        self.is_paused = false;
+        self.fee_burn_bps = 0;
+        self.last_fee_change_at = 0;
+        self.fee_change_cooldown = DEFAULT_FEE_CHANGE_COOLDOWN;
+        self.operator_nonce = 0;
+        self.defer_missing_refunds = false;
+        self.pending_refunds = 0;
+        self.total_resolution_time = 0;
+        self.allow_transfer_hooks = false;
+        self.expiry_behavior = EXPIRY_REFUND_SENDER;
+        self.event_verbosity = EVENT_VERBOSITY_FULL;
+        self.max_lifetime_seconds = 0;
+        self.stale_fee_bps = 0;
+        self.reject_undo_window = 0;
+        self.coupon_signer = Pubkey::default();
+        self.operator_fee_account = Pubkey::default();
+        self.allow_self_transfer = false;
+        self.reset_period(clock.unix_timestamp);
+        self.min_operator_stake = 0;
+        self.operator_stake = 0;
+        self.fee_vault = Pubkey::default();
+        self.notify_memo = false;
+        self.max_extension_seconds = 0;
+        self.fee_in_sol = false;
+        self.sol_fee_lamports = 0;
+        self.collected_sol_fees = 0;
+        self.free_transfer_count = 0;
+        self.stake_vault_bump = 0;
+        self.last_sol_fee_change_at = 0;
+
+        Ok(())
+    }
+
+    /// Whether events should carry optional fields such as memos and reasons
+    pub fn full_events(&self) -> bool {
+        self.event_verbosity == EVENT_VERBOSITY_FULL
+    }
+
+    /// Calculate transfer fee amount, capped so the net amount never drops
+    /// below `MIN_REFUND_BPS` of the transfer
     pub fn calculate_transfer_fee(&self, amount: u64) -> u64 {
-        if self.transfer_fee_bps == 0 {
+        Self::fee_at_bps(self.transfer_fee_bps.min(10000 - MIN_REFUND_BPS), amount)
+    }
+
+    /// Fee rate (bps) applied to `transfer`: its negotiated override, else the
+    /// pool fee, capped like `calculate_transfer_fee`
+    pub fn effective_fee_bps(&self, transfer: &SecureTransfer) -> u16 {
+        // The fee was already paid in SOL at creation, or waived for a new sender
+        if transfer.fee_paid_in_sol || transfer.fee_waived {
+            return 0;
+        }
+        transfer
+            .fee_override_bps
+            .or(transfer.locked_fee_bps)
+            .unwrap_or(self.transfer_fee_bps)
+            .min(10000 - MIN_REFUND_BPS)
+    }
+
+    /// Calculate the fee on `amount` released from `transfer`, applying its
+    /// fee override and coupon discount
+    pub fn calculate_fee_for(&self, transfer: &SecureTransfer, amount: u64) -> u64 {
+        let fee = Self::fee_at_bps(self.effective_fee_bps(transfer), amount);
+        transfer.apply_fee_discount(fee)
+    }
+
+    /// Split a transfer released to its recipient into `(fee, net_amount, change)`.
+    /// An exact-amount transfer pays the recipient exactly `exact_amount`, is
+    /// charged the fee on that, and returns the rest of the escrow as change.
+    pub fn calculate_payout(&self, transfer: &SecureTransfer) -> Result<(u64, u64, u64)> {
+        match transfer.exact_amount {
+            Some(exact_amount) => {
+                let fee = self.calculate_fee_for(transfer, exact_amount);
+                let change = transfer
+                    .amount
+                    .checked_sub(exact_amount)
+                    .and_then(|rest| rest.checked_sub(fee))
+                    .ok_or(HandshakeError::ExactAmountUnderfunded)?;
+                Ok((fee, exact_amount, change))
+            }
+            None => {
+                let fee = self.calculate_fee_for(transfer, transfer.amount);
+                let net_amount = transfer
+                    .amount
+                    .checked_sub(fee)
+                    .ok_or(HandshakeError::CalculationError)?;
+                Ok((fee, net_amount, 0))
+            }
+        }
+    }
+
+    fn fee_at_bps(fee_bps: u16, amount: u64) -> u64 {
+        if fee_bps == 0 {
             return 0;
         }
         (amount as u128)
-            .checked_mul(self.transfer_fee_bps as u128)
+            .checked_mul(fee_bps as u128)
+            .unwrap_or(0)
+            .checked_div(10000)
+            .unwrap_or(0) as u64
+    }
+
+    /// Calculate the portion of a fee that is burned rather than collected
+    pub fn calculate_fee_burn(&self, fee: u64) -> u64 {
+        if self.fee_burn_bps == 0 {
+            return 0;
+        }
+        (fee as u128)
+            .checked_mul(self.fee_burn_bps as u128)
             .unwrap_or(0)
             .checked_div(10000)
             .unwrap_or(0) as u64
     }
 
+    /// Cooldown between fee changes; a zero cooldown (a pool migrated from the
+    /// older layout) falls back to the default rather than disabling it
+    pub fn effective_fee_change_cooldown(&self) -> i64 {
+        if self.fee_change_cooldown == 0 {
+            DEFAULT_FEE_CHANGE_COOLDOWN
+        } else {
+            self.fee_change_cooldown
+        }
+    }
+
+    /// Set the cooldown between fee changes, within the allowed bounds
+    pub fn set_fee_change_cooldown(&mut self, fee_change_cooldown: i64) -> Result<()> {
+        require!(
+            (MIN_FEE_CHANGE_COOLDOWN..=MAX_FEE_CHANGE_COOLDOWN).contains(&fee_change_cooldown),
+            HandshakeError::InvalidTimeWindow
+        );
+        self.fee_change_cooldown = fee_change_cooldown;
+        Ok(())
+    }
+
+    /// Update the transfer fee, enforcing the fee change cooldown
+    pub fn set_transfer_fee_bps(&mut self, transfer_fee_bps: u16, now: i64) -> Result<()> {
+        require!(
+            transfer_fee_bps <= 10000,
+            HandshakeError::InvalidTransferFee
+        );
+
+        let next_change_at = self
+            .last_fee_change_at
+            .checked_add(self.effective_fee_change_cooldown())
+            .ok_or(HandshakeError::MathOverflow)?;
+        require!(now >= next_change_at, HandshakeError::FeeChangeCooldown);
+
+        self.transfer_fee_bps = transfer_fee_bps;
+        self.last_fee_change_at = now;
+        Ok(())
+    }
+
+    /// Update the SOL fee settings, enforcing the same cooldown as the transfer fee
+    pub fn set_sol_fee(
+        &mut self,
+        fee_in_sol: Option<bool>,
+        sol_fee_lamports: Option<u64>,
+        now: i64,
+    ) -> Result<()> {
+        if let Some(sol_fee_lamports) = sol_fee_lamports {
+            require!(
+                sol_fee_lamports <= MAX_SOL_FEE_LAMPORTS,
+                HandshakeError::InvalidFeeConfig
+            );
+        }
+
+        let next_change_at = self
+            .last_sol_fee_change_at
+            .checked_add(self.effective_fee_change_cooldown())
+            .ok_or(HandshakeError::MathOverflow)?;
+        require!(now >= next_change_at, HandshakeError::FeeChangeCooldown);
+
+        if let Some(fee_in_sol) = fee_in_sol {
+            self.fee_in_sol = fee_in_sol;
+        }
+        if let Some(sol_fee_lamports) = sol_fee_lamports {
+            self.sol_fee_lamports = sol_fee_lamports;
+        }
+        self.last_sol_fee_change_at = now;
+        Ok(())
+    }
+
     /// Increment transfer created counter
     pub fn increment_transfers_created(&mut self) -> Result<()> {
         self.total_transfers_created = self
@@ -82,21 +406,59 @@ impl Pool {
         Ok(())
     }
 
-    /// Increment transfer resolved counter
-    pub fn increment_transfers_resolved(&mut self) -> Result<()> {
+    /// Consume the operator nonce for a relayed resolution (replay protection).
+    /// Nonces are strictly sequential, so any nonce below the current one has
+    /// already been used.
+    pub fn consume_operator_nonce(&mut self, nonce: u64) -> Result<()> {
+        require!(
+            nonce >= self.operator_nonce,
+            HandshakeError::NonceAlreadyUsed
+        );
+        require!(
+            nonce == self.operator_nonce,
+            HandshakeError::InvalidOperatorNonce
+        );
+        self.operator_nonce = self
+            .operator_nonce
+            .checked_add(1)
+            .ok_or(HandshakeError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Increment transfer resolved counter and accumulate resolution latency
+    pub fn increment_transfers_resolved(&mut self, created_at: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
         self.total_transfers_resolved = self
             .total_transfers_resolved
             .checked_add(1)
             .ok_or(HandshakeError::MathOverflow)?;
+        // Clock skew can't make a resolution take negative time
+        let elapsed = now.saturating_sub(created_at).max(0) as u128;
+        self.total_resolution_time = self
+            .total_resolution_time
+            .checked_add(elapsed)
+            .ok_or(HandshakeError::MathOverflow)?;
         Ok(())
     }
 
+    /// Average seconds from creation to resolution (0 if nothing resolved)
+    pub fn average_resolution_time(&self) -> u64 {
+        if self.total_transfers_resolved == 0 {
+            return 0;
+        }
+        (self.total_resolution_time / self.total_transfers_resolved as u128) as u64
+    }
+
     /// Add deposit (when creating transfer)
     pub fn add_deposit(&mut self, amount: u64) -> Result<()> {
         self.total_deposits = self
             .total_deposits
             .checked_add(amount)
             .ok_or(HandshakeError::MathOverflow)?;
+        self.period_deposits = self
+            .period_deposits
+            .checked_add(amount)
+            .ok_or(HandshakeError::MathOverflow)?;
         self.total_escrowed = self
             .total_escrowed
             .checked_add(amount)
@@ -110,10 +472,23 @@ impl Pool {
             .total_withdrawals
             .checked_add(amount)
             .ok_or(HandshakeError::MathOverflow)?;
+        self.period_withdrawals = self
+            .period_withdrawals
+            .checked_add(amount)
+            .ok_or(HandshakeError::MathOverflow)?;
         self.total_escrowed = self
             .total_escrowed
             .checked_sub(amount)
-            .ok_or(HandshakeError::MathOverflow)?;
+            .ok_or(HandshakeError::EscrowMismatch)?;
+        Ok(())
+    }
+
+    /// Check that `amount` is still escrowed and held by the pool token account
+    pub fn validate_escrow(&self, pool_token_balance: u64, amount: u64) -> Result<()> {
+        require!(
+            pool_token_balance >= amount && self.total_escrowed >= amount,
+            HandshakeError::EscrowMismatch
+        );
         Ok(())
     }
 
@@ -123,9 +498,30 @@ impl Pool {
             .collected_fees
             .checked_add(amount)
             .ok_or(HandshakeError::MathOverflow)?;
+        self.period_fees_collected = self
+            .period_fees_collected
+            .checked_add(amount)
+            .ok_or(HandshakeError::MathOverflow)?;
         Ok(())
     }
 
+    /// Validate the operator's stake covers the pool's minimum
+    pub fn validate_operator_stake(&self) -> Result<()> {
+        require!(
+            self.operator_stake >= self.min_operator_stake,
+            HandshakeError::InsufficientOperatorStake
+        );
+        Ok(())
+    }
+
+    /// Zero the accounting period counters and start a new period at `now`
+    pub fn reset_period(&mut self, now: i64) {
+        self.period_deposits = 0;
+        self.period_withdrawals = 0;
+        self.period_fees_collected = 0;
+        self.period_started_at = now;
+    }
+
     /// Reset collected fees to zero (after withdrawal)
     pub fn reset_collected_fees(&mut self) {
         self.collected_fees = 0;
@@ -133,6 +529,24 @@ impl Pool {
 
     /// Check if pool has outstanding transfers
     pub fn has_outstanding_transfers(&self) -> bool {
-        self.total_transfers_created > self.total_transfers_resolved
+        self.total_transfers_created > self.total_transfers_resolved || self.pending_refunds > 0
+    }
+
+    /// Track a deferred refund (tokens stay escrowed until claimed)
+    pub fn add_pending_refund(&mut self) -> Result<()> {
+        self.pending_refunds = self
+            .pending_refunds
+            .checked_add(1)
+            .ok_or(HandshakeError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Clear a deferred refund once it has been paid out
+    pub fn resolve_pending_refund(&mut self) -> Result<()> {
+        self.pending_refunds = self
+            .pending_refunds
+            .checked_sub(1)
+            .ok_or(HandshakeError::MathOverflow)?;
+        Ok(())
     }
 }
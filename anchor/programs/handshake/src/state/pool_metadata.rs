@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::HandshakeError;
+
+/// Human-readable details for listing a pool, PDA derived from the pool
+#[account]
+pub struct PoolMetadata {
+    /// Version for upgrades
+    pub version: u8,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Pool this metadata describes
+    pub pool: Pubkey,
+
+    /// Display name (at most MAX_POOL_NAME_LEN bytes)
+    pub name: String,
+
+    /// Off-chain metadata URI, e.g. a JSON document with a logo (at most MAX_POOL_URI_LEN bytes)
+    pub uri: String,
+
+    /// Padding for future upgrades
+    pub _padding: [u8; 32],
+}
+
+impl PoolMetadata {
+    pub const SPACE: usize = 8 + // discriminator
+        1 + // version
+        1 + // bump
+        32 + // pool
+        4 + MAX_POOL_NAME_LEN + // name
+        4 + MAX_POOL_URI_LEN + // uri
+        32; // _padding
+
+    /// Set the name and URI, enforcing their length bounds
+    pub fn set(&mut self, name: String, uri: String) -> Result<()> {
+        require!(
+            name.len() <= MAX_POOL_NAME_LEN && uri.len() <= MAX_POOL_URI_LEN,
+            HandshakeError::PoolMetadataTooLong
+        );
+        self.name = name;
+        self.uri = uri;
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::errors::HandshakeError;
+
+/// Minimum amount a recipient expects to receive per transfer in a pool
+#[account]
+pub struct RecipientExpectation {
+    /// Version for upgrades
+    pub version: u8,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Pool this expectation applies to
+    pub pool: Pubkey,
+
+    /// Recipient who registered the expectation
+    pub recipient: Pubkey,
+
+    /// Transfers below this amount are refused at creation
+    pub expected_amount: u64,
+
+    /// Padding for future upgrades
+    pub _padding: [u8; 32],
+}
+
+impl RecipientExpectation {
+    pub const SPACE: usize = 8 + // discriminator
+        1 + // version
+        1 + // bump
+        32 + // pool
+        32 + // recipient
+        8 + // expected_amount
+        32; // _padding
+
+    /// Validate `amount` against the expectation stored at `expectation_info`,
+    /// if the recipient has registered one
+    pub fn validate_amount(
+        expectation_info: &AccountInfo,
+        program_id: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        if expectation_info.owner == program_id && !expectation_info.data_is_empty() {
+            let data = expectation_info.try_borrow_data()?;
+            let expectation = RecipientExpectation::try_deserialize(&mut &data[..])?;
+            require!(
+                amount >= expectation.expected_amount,
+                HandshakeError::AmountBelowExpected
+            );
+        }
+        Ok(())
+    }
+}
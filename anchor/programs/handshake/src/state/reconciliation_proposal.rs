@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+/// A pending accounting correction for a pool, executable once its timelock passes
+#[account]
+pub struct ReconciliationProposal {
+    /// Version for upgrades
+    pub version: u8,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Pool whose counters will be corrected
+    pub pool: Pubkey,
+
+    /// Program upgrade authority that proposed the correction
+    pub admin: Pubkey,
+
+    /// Tokens actually owed to open transfers and deferred refunds
+    pub active_escrow_total: u64,
+
+    /// When the correction was proposed (unix timestamp)
+    pub proposed_at: i64,
+
+    /// Earliest time `reconcile_accounting` may apply it (unix timestamp)
+    pub executable_at: i64,
+
+    /// Padding for future upgrades
+    pub _padding: [u8; 32],
+}
+
+impl ReconciliationProposal {
+    pub const SPACE: usize = 8 + // discriminator
+        1 + // version
+        1 + // bump
+        32 + // pool
+        32 + // admin
+        8 + // active_escrow_total
+        8 + // proposed_at
+        8 + // executable_at
+        32; // _padding
+}
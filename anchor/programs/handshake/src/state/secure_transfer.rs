@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+use crate::constants::{MAX_MILESTONES, TRANSFER_VERSION};
 use crate::errors::HandshakeError;
 
 #[account]
@@ -39,8 +41,68 @@ pub struct SecureTransfer {
     /// Travel rule compliance data hash
     pub compliance_hash: Option<[u8; 32]>,
 
+    /// Hash of (transfer pubkey || secret code) the recipient must present to claim
+    pub claim_code_hash: Option<[u8; 32]>,
+
+    /// Portion of the amount paid to whoever expires the transfer
+    pub keeper_tip: u64,
+
+    /// Hash of (amount || salt) for sealed transfers, cleared once revealed
+    pub amount_commitment: Option<[u8; 32]>,
+
+    /// Token account rejections refund to instead of the sender's ATA
+    pub refund_token_account: Option<Pubkey>,
+
+    /// Funds are released only by mutual_accept (operator and recipient both sign)
+    pub requires_mutual: bool,
+
+    /// When a soft-rejected transfer can be finalized (undo window end)
+    pub reject_finalize_after: i64,
+
+    /// Reason recorded by a soft reject, reported when it is finalized
+    pub reject_reason: Option<u8>,
+
+    /// Fee discount (bps of the pool fee) from a redeemed coupon
+    pub fee_discount_bps: u16,
+
+    /// Negotiated fee rate (bps) replacing the pool fee for this transfer
+    pub fee_override_bps: Option<u16>,
+
+    /// Staged release schedule (empty = released in one claim)
+    pub milestones: Vec<Milestone>,
+
+    /// Amount escrowed when the milestones were set; fractions are of this total
+    pub milestone_total: u64,
+
+    /// Amount the recipient receives on accept; the excess over it and its fee is change
+    pub exact_amount: Option<u64>,
+
+    /// Seconds the claim deadline has been extended by so far
+    pub extended_by: i64,
+
+    /// Whether the fee was paid in SOL at creation (no token fee at release)
+    pub fee_paid_in_sol: bool,
+
+    /// Whether the fee is waived under the pool's free-transfer allowance (set at creation)
+    pub fee_waived: bool,
+
+    /// Pool fee (bps) at creation, locked for exact-amount transfers so a later
+    /// fee change can't leave the escrow short of the payout plus fee
+    pub locked_fee_bps: Option<u16>,
+
+    /// SOL fee escrowed in this account at creation; moved to the pool when the
+    /// transfer is released, returned to the sender with the rent otherwise
+    pub sol_fee_lamports: u64,
+
+    /// When the transfer becomes stale and can only be force-resolved, from the
+    /// pool's max lifetime at creation (0 = never)
+    pub stale_at: i64,
+
+    /// Pool stale fee (bps) at creation, kept when the transfer is force-resolved
+    pub stale_fee_bps: u16,
+
     /// Padding for future upgrades
-    pub _padding: [u8; 64],
+    pub _padding: [u8; 2],
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
@@ -51,6 +113,21 @@ pub enum TransferStatus {
     Rejected,
     Expired,
     Declined,
+    Settled,
+    ForceResolved,
+    RejectedPending,
+}
+
+/// One staged release: `bps` of the milestone total, released by the operator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct Milestone {
+    pub bps: u16,
+    pub released: bool,
+}
+
+impl Milestone {
+    pub const SPACE: usize = 2 + // bps
+        1; // released
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -83,7 +160,26 @@ impl SecureTransfer {
         (1 + (1 + 64)) + // release_conditions Option
         64 + // memo
         (1 + 32) + // compliance_hash Option
-        64; // _padding
+        (1 + 32) + // claim_code_hash Option
+        8 + // keeper_tip
+        (1 + 32) + // amount_commitment Option
+        (1 + 32) + // refund_token_account Option
+        1 + // requires_mutual
+        8 + // reject_finalize_after
+        (1 + 1) + // reject_reason Option
+        2 + // fee_discount_bps
+        (1 + 2) + // fee_override_bps Option
+        (4 + MAX_MILESTONES * Milestone::SPACE) + // milestones Vec
+        8 + // milestone_total
+        (1 + 8) + // exact_amount Option
+        8 + // extended_by
+        1 + // fee_paid_in_sol
+        1 + // fee_waived
+        (1 + 2) + // locked_fee_bps Option
+        8 + // sol_fee_lamports
+        8 + // stale_at
+        2 + // stale_fee_bps
+        2; // _padding
 
     /// Initialize a new transfer
     pub fn initialize(
@@ -126,7 +222,7 @@ impl SecureTransfer {
             );
         }
 
-        self.version = 1;
+        self.version = TRANSFER_VERSION;
         self.bump = bump;
         self.nonce = nonce;
         self.sender = sender;
@@ -204,6 +300,183 @@ impl SecureTransfer {
         Ok(())
     }
 
+    /// Apply the transfer's coupon discount to a pool fee
+    pub fn apply_fee_discount(&self, fee: u64) -> u64 {
+        if self.fee_discount_bps == 0 {
+            return fee;
+        }
+        let discount = (fee as u128)
+            .checked_mul(self.fee_discount_bps as u128)
+            .unwrap_or(0)
+            .checked_div(10000)
+            .unwrap_or(0) as u64;
+        fee.saturating_sub(discount)
+    }
+
+    /// Validate the transfer has no milestone schedule (released in one go)
+    pub fn validate_no_milestones(&self) -> Result<()> {
+        require!(self.milestones.is_empty(), HandshakeError::MilestonesPending);
+        Ok(())
+    }
+
+    /// Set the milestone schedule; fractions must cover the whole amount
+    pub fn set_milestones(&mut self, milestones_bps: &[u16]) -> Result<()> {
+        self.validate_active()?;
+        self.validate_revealed()?;
+        self.validate_no_milestones()?;
+        require!(self.exact_amount.is_none(), HandshakeError::InvalidMilestones);
+        require!(
+            !milestones_bps.is_empty() && milestones_bps.len() <= MAX_MILESTONES,
+            HandshakeError::InvalidMilestones
+        );
+        let total_bps = milestones_bps
+            .iter()
+            .try_fold(0u16, |total, bps| {
+                if *bps == 0 { None } else { total.checked_add(*bps) }
+            })
+            .ok_or(HandshakeError::InvalidMilestones)?;
+        require!(total_bps == 10000, HandshakeError::InvalidMilestones);
+
+        self.milestones = milestones_bps
+            .iter()
+            .map(|bps| Milestone { bps: *bps, released: false })
+            .collect();
+        self.milestone_total = self.amount;
+        Ok(())
+    }
+
+    /// Mark milestone `index` released and return the amount it releases.
+    /// The last outstanding milestone releases whatever remains, so the
+    /// releases always sum to the milestone total.
+    pub fn release_milestone(&mut self, index: usize) -> Result<u64> {
+        self.validate_active()?;
+        let milestone = self
+            .milestones
+            .get(index)
+            .ok_or(HandshakeError::InvalidMilestones)?;
+        require!(!milestone.released, HandshakeError::MilestoneAlreadyReleased);
+
+        let outstanding = self.milestones.iter().filter(|m| !m.released).count();
+        let portion = if outstanding == 1 {
+            self.amount
+        } else {
+            ((self.milestone_total as u128)
+                .checked_mul(milestone.bps as u128)
+                .ok_or(HandshakeError::MathOverflow)?
+                / 10000) as u64
+        };
+
+        self.milestones[index].released = true;
+        self.amount = self
+            .amount
+            .checked_sub(portion)
+            .ok_or(HandshakeError::CalculationError)?;
+        Ok(portion)
+    }
+
+    /// Push the claim deadline back by `extension` seconds, keeping the total
+    /// extension within `max_extension`. Only unexpired deadlines can move.
+    pub fn extend_deadline(&mut self, extension: i64, max_extension: i64, now: i64) -> Result<i64> {
+        self.validate_active()?;
+        require!(
+            self.claimable_until > 0 && now <= self.claimable_until,
+            HandshakeError::InvalidTimeWindow
+        );
+        require!(extension > 0, HandshakeError::InvalidExtension);
+
+        let extended_by = self
+            .extended_by
+            .checked_add(extension)
+            .ok_or(HandshakeError::MathOverflow)?;
+        require!(extended_by <= max_extension, HandshakeError::ExtensionCapExceeded);
+
+        self.claimable_until = self
+            .claimable_until
+            .checked_add(extension)
+            .ok_or(HandshakeError::MathOverflow)?;
+        self.extended_by = extended_by;
+        Ok(self.claimable_until)
+    }
+
+    /// Whether every milestone has been released
+    pub fn all_milestones_released(&self) -> bool {
+        !self.milestones.is_empty() && self.milestones.iter().all(|m| m.released)
+    }
+
+    /// Validate the transfer can be released without both parties signing
+    pub fn validate_single_party_release(&self) -> Result<()> {
+        require!(!self.requires_mutual, HandshakeError::MutualAcceptRequired);
+        Ok(())
+    }
+
+    /// Fix the pool's staleness terms at creation, so later changes to the
+    /// pool's max lifetime or stale fee only affect new transfers
+    pub fn lock_stale_terms(&mut self, max_lifetime_seconds: i64, stale_fee_bps: u16) -> Result<()> {
+        self.stale_at = if max_lifetime_seconds == 0 {
+            0
+        } else {
+            self.created_at
+                .checked_add(max_lifetime_seconds)
+                .ok_or(HandshakeError::MathOverflow)?
+        };
+        self.stale_fee_bps = stale_fee_bps;
+        Ok(())
+    }
+
+    /// Whether the transfer has outlived the max lifetime locked at creation
+    pub fn is_stale(&self, now: i64) -> bool {
+        self.stale_at != 0 && now > self.stale_at
+    }
+
+    /// Validate the transfer has not outlived the max lifetime locked at creation
+    pub fn validate_not_stale(&self) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(!self.is_stale(clock.unix_timestamp), HandshakeError::TransferStale);
+        Ok(())
+    }
+
+    /// Calculate the fee kept when force-resolving, at the rate locked at creation
+    pub fn calculate_stale_fee(&self) -> u64 {
+        (self.amount as u128)
+            .checked_mul(self.stale_fee_bps as u128)
+            .unwrap_or(0)
+            .checked_div(10000)
+            .unwrap_or(0) as u64
+    }
+
+    /// Validate a sealed transfer's amount has been revealed
+    pub fn validate_revealed(&self) -> Result<()> {
+        require!(
+            self.amount_commitment.is_none(),
+            HandshakeError::AmountNotRevealed
+        );
+        Ok(())
+    }
+
+    /// Validate a revealed (amount, salt) against the stored commitment
+    pub fn validate_amount_reveal(&self, amount: u64, salt: &[u8; 32]) -> Result<()> {
+        let expected = self
+            .amount_commitment
+            .ok_or(HandshakeError::InvalidAmountReveal)?;
+        let hash = hashv(&[&amount.to_le_bytes(), salt]);
+        require!(hash.to_bytes() == expected, HandshakeError::InvalidAmountReveal);
+        require!(
+            amount > 0 && amount <= self.amount,
+            HandshakeError::InvalidAmountReveal
+        );
+        Ok(())
+    }
+
+    /// Validate the claim code preimage when the transfer requires one
+    pub fn validate_claim_code(&self, transfer: &Pubkey, claim_code: Option<[u8; 32]>) -> Result<()> {
+        if let Some(expected) = self.claim_code_hash {
+            let code = claim_code.ok_or(HandshakeError::InvalidClaimCode)?;
+            let hash = hashv(&[transfer.as_ref(), &code]);
+            require!(hash.to_bytes() == expected, HandshakeError::InvalidClaimCode);
+        }
+        Ok(())
+    }
+
     /// Mark as claimed
     pub fn mark_as_claimed(&mut self) -> Result<()> {
         self.validate_active()?;
@@ -225,6 +498,48 @@ impl SecureTransfer {
         Ok(())
     }
 
+    /// Validate transfer is soft-rejected, awaiting undo or finalization
+    pub fn validate_reject_pending(&self) -> Result<()> {
+        require!(
+            self.status == TransferStatus::RejectedPending,
+            HandshakeError::TransferNotRejectPending
+        );
+        Ok(())
+    }
+
+    /// Soft-reject: hold the funds until `finalize_after`, when the reject can be finalized
+    pub fn mark_as_reject_pending(&mut self, reason: Option<u8>, finalize_after: i64) -> Result<()> {
+        self.validate_active()?;
+        self.status = TransferStatus::RejectedPending;
+        self.reject_finalize_after = finalize_after;
+        self.reject_reason = reason;
+        Ok(())
+    }
+
+    /// Undo a soft reject within its undo window, restoring the transfer to active
+    pub fn undo_reject_pending(&mut self, now: i64) -> Result<()> {
+        self.validate_reject_pending()?;
+        require!(
+            now < self.reject_finalize_after,
+            HandshakeError::RejectUndoWindowClosed
+        );
+        self.status = TransferStatus::Active;
+        self.reject_finalize_after = 0;
+        self.reject_reason = None;
+        Ok(())
+    }
+
+    /// Finalize a soft reject once its undo window has passed
+    pub fn finalize_reject_pending(&mut self, now: i64) -> Result<()> {
+        self.validate_reject_pending()?;
+        require!(
+            now >= self.reject_finalize_after,
+            HandshakeError::RejectUndoWindowOpen
+        );
+        self.status = TransferStatus::Rejected;
+        Ok(())
+    }
+
     /// Mark as expired
     pub fn mark_as_expired(&mut self) -> Result<()> {
         self.validate_active()?;
@@ -238,4 +553,18 @@ impl SecureTransfer {
         self.status = TransferStatus::Declined;
         Ok(())
     }
+
+    /// Mark as settled (split between recipient and sender)
+    pub fn mark_as_settled(&mut self) -> Result<()> {
+        self.validate_active()?;
+        self.status = TransferStatus::Settled;
+        Ok(())
+    }
+
+    /// Mark as force-resolved (stale, refunded to sender)
+    pub fn mark_as_force_resolved(&mut self) -> Result<()> {
+        self.validate_active()?;
+        self.status = TransferStatus::ForceResolved;
+        Ok(())
+    }
 }
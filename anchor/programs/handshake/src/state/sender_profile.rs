@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::errors::HandshakeError;
+use super::SecureTransfer;
+
+/// Per-pool history of a sender, used for the pool's free-transfer allowance
+#[account]
+pub struct SenderProfile {
+    /// Version for upgrades
+    pub version: u8,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Pool the profile belongs to
+    pub pool: Pubkey,
+
+    /// Sender the profile tracks
+    pub sender: Pubkey,
+
+    /// Fee-waived transfers this sender has open or released in the pool;
+    /// refunded ones are given back
+    pub free_transfers_used: u32,
+
+    /// Padding for future upgrades
+    pub _padding: [u8; 32],
+}
+
+impl SenderProfile {
+    pub const SPACE: usize = 8 + // discriminator
+        1 + // version
+        1 + // bump
+        32 + // pool
+        32 + // sender
+        4 + // free_transfers_used
+        32; // _padding
+
+    /// Record a created transfer. Returns whether the sender still has any of
+    /// the pool's `free_transfer_count` left, in which case one is used up and
+    /// the transfer's fee is waived.
+    pub fn record_transfer(&mut self, free_transfer_count: u32) -> Result<bool> {
+        if self.free_transfers_used >= free_transfer_count {
+            return Ok(false);
+        }
+        self.free_transfers_used = self
+            .free_transfers_used
+            .checked_add(1)
+            .ok_or(HandshakeError::MathOverflow)?;
+        Ok(true)
+    }
+
+    /// Give a refunded transfer's waiver back to the sender's allowance
+    pub fn release_transfer(&mut self, transfer: &SecureTransfer) {
+        if transfer.fee_waived {
+            self.free_transfers_used = self.free_transfers_used.saturating_sub(1);
+        }
+    }
+}
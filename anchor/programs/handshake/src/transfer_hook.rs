@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, transfer_hook, BaseStateWithExtensions, StateWithExtensions},
+    onchain::invoke_transfer_checked,
+};
+use anchor_spl::token_interface::{transfer_checked, TransferChecked};
+
+/// Whether `mint` is a Token-2022 mint with a TransferHook program configured
+pub fn has_transfer_hook(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(transfer_hook::get_program_id(&state).is_some())
+}
+
+/// Fee the mint's Token-2022 transfer-fee extension withholds when `amount`
+/// is transferred in `epoch` (0 for mints without the extension)
+pub fn mint_transfer_fee(mint: &AccountInfo, epoch: u64, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Ok(config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?),
+        Err(_) => Ok(0),
+    }
+}
+
+/// `transfer_checked` that forwards `additional_accounts` (the mint's transfer
+/// hook program, validation account and extra metas) to Token-2022. Without
+/// additional accounts this is a plain `transfer_checked`.
+pub fn transfer_checked_with_hooks<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    additional_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    if additional_accounts.is_empty() {
+        return transfer_checked(ctx, amount, decimals);
+    }
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        additional_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
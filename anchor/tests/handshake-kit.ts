@@ -60,6 +60,7 @@ const POOL_SEED = new TextEncoder().encode("pool");
 const SENDER_SEED = new TextEncoder().encode("sender");
 const RECIPIENT_SEED = new TextEncoder().encode("recipient");
 const NONCE_SEED = new TextEncoder().encode("nonce");
+const EXPECTATION_SEED = new TextEncoder().encode("expectation");
 
 const SYSTEM_PROGRAM_ADDRESS: Address = address(
   "11111111111111111111111111111111"
@@ -145,6 +146,18 @@ async function findTransferPda(
   });
 }
 
+async function findExpectationPda(
+  programAddress: Address,
+  poolPda: Address,
+  recipient: Address
+): Promise<readonly [Address, number]> {
+  const encoder = getAddressEncoder();
+  return getProgramDerivedAddress({
+    programAddress,
+    seeds: [EXPECTATION_SEED, encoder.encode(poolPda), encoder.encode(recipient)],
+  });
+}
+
 async function findAta(
   mint: Address,
   owner: Address
@@ -271,7 +284,8 @@ function createTransferAccounts(
   mint: Address,
   transferPda: Address,
   senderAta: Address,
-  poolAta: Address
+  poolAta: Address,
+  expectationPda: Address
 ) {
   return {
     sender: toPubkey(sender),
@@ -280,6 +294,11 @@ function createTransferAccounts(
     poolTokenAccount: toPubkey(poolAta),
     senderTokenAccount: toPubkey(senderAta),
     transfer: toPubkey(transferPda),
+    recipientExpectation: toPubkey(expectationPda),
+    instructionsSysvar: null,
+    couponRedemption: null,
+    refundTokenAccount: null,
+    senderProfile: null,
     tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
     systemProgram: toPubkey(SYSTEM_PROGRAM_ADDRESS),
     associatedTokenProgram: toPubkey(ASSOCIATED_TOKEN_PROGRAM_ADDRESS),
@@ -302,6 +321,7 @@ function cancelTransferAccounts(
     senderTokenAccount: toPubkey(senderAta),
     transfer: toPubkey(transferPda),
     tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+    senderProfile: null,
   };
 }
 
@@ -323,6 +343,9 @@ function claimTransferAccounts(
     transfer: toPubkey(transferPda),
     sender: toPubkey(sender),
     tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+    feeVault: null,
+    memoProgram: null,
+    senderTokenAccount: null,
   };
 }
 
@@ -344,6 +367,10 @@ function rejectTransferAccounts(
     transfer: toPubkey(transferPda),
     sender: toPubkey(sender),
     tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+    refundTokenAccount: null,
+    pendingRefund: null,
+    systemProgram: null,
+    senderProfile: null,
   };
 }
 
@@ -365,6 +392,7 @@ function declineTransferAccounts(
     transfer: toPubkey(transferPda),
     sender: toPubkey(sender),
     tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+    senderProfile: null,
   };
 }
 
@@ -385,7 +413,11 @@ function expireTransferAccounts(
     senderTokenAccount: toPubkey(senderAta),
     transfer: toPubkey(transferPda),
     sender: toPubkey(sender),
+    recipientTokenAccount: null,
+    callerTokenAccount: null,
     tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+    feeVault: null,
+    senderProfile: null,
   };
 }
 
@@ -405,6 +437,7 @@ function destroyTransferAccounts(
     operatorTokenAccount: toPubkey(operatorAta),
     transfer: toPubkey(transferPda),
     tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+    senderProfile: null,
   };
 }
 
@@ -467,6 +500,8 @@ describe("handshake (kit)", () => {
   let thirdPartyAta: Address;
   let zeroFeePoolAta: Address;
   let feePoolAta: Address;
+  let zeroFeeExpectationPda: Address;
+  let feeExpectationPda: Address;
 
   before(async () => {
     // ── Generate dual keypairs (kit KeyPairSigner + legacy Keypair from same seed) ──
@@ -518,6 +553,8 @@ describe("handshake (kit)", () => {
     thirdPartyAta = await findAta(mint, thirdParty.address);
     zeroFeePoolAta = await findAta(mint, zeroFeePoolPda);
     feePoolAta = await findAta(mint, feePoolPda);
+    [zeroFeeExpectationPda] = await findExpectationPda(programId, zeroFeePoolPda, recipient.address);
+    [feeExpectationPda] = await findExpectationPda(programId, feePoolPda, recipient.address);
   });
 
   // ═══════════════════════════════════════════════════════════════════════════
@@ -527,7 +564,7 @@ describe("handshake (kit)", () => {
   describe("A. Pool Initialization", () => {
     it("A1. initializes a pool with 0% fee", async () => {
       await program.methods
-        .initPool(toPubkey(zeroFeePoolId), 0)
+        .initPool(toPubkey(zeroFeePoolId), 0, "", "")
         .accounts({
           operator: toPubkey(operator),
           mint: toPubkey(mint),
@@ -558,7 +595,7 @@ describe("handshake (kit)", () => {
 
     it("A2. initializes a pool with 2.5% fee (250 bps)", async () => {
       await program.methods
-        .initPool(toPubkey(feePoolId), FEE_BPS)
+        .initPool(toPubkey(feePoolId), FEE_BPS, "", "")
         .accounts({
           operator: toPubkey(operator),
           mint: toPubkey(mint),
@@ -584,7 +621,7 @@ describe("handshake (kit)", () => {
 
       try {
         await program.methods
-          .initPool(toPubkey(badPoolId), 10001)
+          .initPool(toPubkey(badPoolId), 10001, "", "")
           .accounts({
             operator: toPubkey(operator),
             mint: toPubkey(mint),
@@ -625,9 +662,16 @@ describe("handshake (kit)", () => {
           TRANSFER_AMOUNT,
           "test cancel",
           new BN(0),
+          new BN(0),
+          null,
+          new BN(0),
+          null,
+          false,
+          null,
+          null,
           new BN(0)
         )
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -677,8 +721,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -711,8 +755,8 @@ describe("handshake (kit)", () => {
       const senderBalBefore = await getTokenBalance(senderAta);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "expire test", new BN(0), claimableUntil)
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "expire test", new BN(0), claimableUntil, null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -741,8 +785,8 @@ describe("handshake (kit)", () => {
       const claimableUntil = new BN(now + 3600);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "not expired", new BN(0), claimableUntil)
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "not expired", new BN(0), claimableUntil, null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -770,8 +814,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "no deadline", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "no deadline", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -813,8 +857,8 @@ describe("handshake (kit)", () => {
 
       // Create
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "claim test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "claim test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -824,7 +868,7 @@ describe("handshake (kit)", () => {
 
       // Claim
       await program.methods
-        .claimTransfer()
+        .claimTransfer(null)
         .accounts(claimTransferAccounts(recipient.address, sender.address, feePoolPda, mint, transferPda, recipientAta, feePoolAta))
         .signers([recipientLegacy])
         .rpc();
@@ -857,14 +901,14 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth claim", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth claim", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
       try {
         await program.methods
-          .claimTransfer()
+          .claimTransfer(null)
           .accounts(claimTransferAccounts(thirdParty.address, sender.address, feePoolPda, mint, transferPda, thirdPartyAta, feePoolAta))
           .signers([thirdPartyLegacy])
           .rpc();
@@ -890,14 +934,14 @@ describe("handshake (kit)", () => {
       const claimableUntil = new BN(now + 7200);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "early claim", claimableAfter, claimableUntil)
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "early claim", claimableAfter, claimableUntil, null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
       try {
         await program.methods
-          .claimTransfer()
+          .claimTransfer(null)
           .accounts(claimTransferAccounts(recipient.address, sender.address, feePoolPda, mint, transferPda, recipientAta, feePoolAta))
           .signers([recipientLegacy])
           .rpc();
@@ -922,8 +966,8 @@ describe("handshake (kit)", () => {
       const poolFeesBefore = (await program.account.pool.fetch(toPubkey(feePoolPda))).collectedFees;
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "reject test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "reject test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -947,8 +991,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth reject", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth reject", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -987,8 +1031,8 @@ describe("handshake (kit)", () => {
       const poolFeesBefore = (await program.account.pool.fetch(toPubkey(feePoolPda))).collectedFees;
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "decline test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "decline test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -1015,8 +1059,8 @@ describe("handshake (kit)", () => {
       const senderBalBefore = await getTokenBalance(senderAta);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "no reason", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "no reason", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -1038,8 +1082,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth decline", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth decline", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -1067,8 +1111,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "cancel first", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "cancel first", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -1102,8 +1146,8 @@ describe("handshake (kit)", () => {
 
       try {
         await program.methods
-          .createTransfer(toPubkey(recipient.address), nonce, new BN(0), "zero amount", new BN(0), new BN(0))
-          .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+          .createTransfer(toPubkey(recipient.address), nonce, new BN(0), "zero amount", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
           .signers([senderLegacy])
           .rpc();
         assert.fail("Should fail with zero amount");
@@ -1119,8 +1163,8 @@ describe("handshake (kit)", () => {
       const longMemo = "x".repeat(65);
       try {
         await program.methods
-          .createTransfer(toPubkey(recipient.address), nonce, new BN(1_000_000), longMemo, new BN(0), new BN(0))
-          .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+          .createTransfer(toPubkey(recipient.address), nonce, new BN(1_000_000), longMemo, new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
           .signers([senderLegacy])
           .rpc();
         assert.fail("Should fail with long memo");
@@ -1140,8 +1184,8 @@ describe("handshake (kit)", () => {
 
       try {
         await program.methods
-          .createTransfer(toPubkey(recipient.address), nonce, new BN(1_000_000), "paused", new BN(0), new BN(0))
-          .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+          .createTransfer(toPubkey(recipient.address), nonce, new BN(1_000_000), "paused", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
           .signers([senderLegacy])
           .rpc();
         assert.fail("Should fail when pool is paused");
@@ -1178,6 +1222,7 @@ describe("handshake (kit)", () => {
           poolTokenAccount: toPubkey(feePoolAta),
           operatorTokenAccount: toPubkey(operatorAta),
           tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+          feeVault: null,
         })
         .rpc();
 
@@ -1197,13 +1242,13 @@ describe("handshake (kit)", () => {
       const amount = new BN(1000 * 1_000_000);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, amount, "fee gen", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, amount, "fee gen", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, feePoolPda, mint, transferPda, senderAta, feePoolAta, feeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
       await program.methods
-        .claimTransfer()
+        .claimTransfer(null)
         .accounts(claimTransferAccounts(recipient.address, sender.address, feePoolPda, mint, transferPda, recipientAta, feePoolAta))
         .signers([recipientLegacy])
         .rpc();
@@ -1218,6 +1263,7 @@ describe("handshake (kit)", () => {
             poolTokenAccount: toPubkey(feePoolAta),
             operatorTokenAccount: toPubkey(senderAta),
             tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+            feeVault: null,
           })
           .signers([senderLegacy])
           .rpc();
@@ -1236,6 +1282,7 @@ describe("handshake (kit)", () => {
           poolTokenAccount: toPubkey(feePoolAta),
           operatorTokenAccount: toPubkey(operatorAta),
           tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
+          feeVault: null,
         })
         .rpc();
     });
@@ -1253,8 +1300,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "destroy test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "destroy test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -1290,8 +1337,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "not paused", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "not paused", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -1318,8 +1365,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth destroy", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, TRANSFER_AMOUNT, "auth destroy", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -1400,15 +1447,15 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, new BN(100 * 1_000_000), "reset block", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, new BN(100 * 1_000_000), "reset block", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
       try {
         await program.methods
           .resetPool()
-          .accounts({ operator: toPubkey(operator), pool: toPubkey(zeroFeePoolPda) })
+          .accounts({ operator: toPubkey(operator), pool: toPubkey(zeroFeePoolPda), feeVault: null })
           .rpc();
         assert.fail("Should fail with outstanding transfers");
       } catch (err: any) {
@@ -1432,7 +1479,7 @@ describe("handshake (kit)", () => {
 
       await program.methods
         .resetPool()
-        .accounts({ operator: toPubkey(operator), pool: toPubkey(zeroFeePoolPda) })
+        .accounts({ operator: toPubkey(operator), pool: toPubkey(zeroFeePoolPda), feeVault: null })
         .rpc();
 
       pool = await program.account.pool.fetch(toPubkey(zeroFeePoolPda));
@@ -1455,8 +1502,8 @@ describe("handshake (kit)", () => {
       const [transferPda] = await findTransferPda(programId, sender.address, recipient.address, nonce);
 
       await program.methods
-        .createTransfer(toPubkey(recipient.address), nonce, new BN(100 * 1_000_000), "close block", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta))
+        .createTransfer(toPubkey(recipient.address), nonce, new BN(100 * 1_000_000), "close block", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.address, zeroFeePoolPda, mint, transferPda, senderAta, zeroFeePoolAta, zeroFeeExpectationPda))
         .signers([senderLegacy])
         .rpc();
 
@@ -1469,6 +1516,8 @@ describe("handshake (kit)", () => {
             mint: toPubkey(mint),
             poolTokenAccount: toPubkey(zeroFeePoolAta),
             operatorTokenAccount: toPubkey(operatorAta),
+            feeVault: null,
+            stakeVault: null,
             tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
           })
           .rpc();
@@ -1497,6 +1546,8 @@ describe("handshake (kit)", () => {
           mint: toPubkey(mint),
           poolTokenAccount: toPubkey(zeroFeePoolAta),
           operatorTokenAccount: toPubkey(operatorAta),
+          feeVault: null,
+          stakeVault: null,
           tokenProgram: toPubkey(TOKEN_PROGRAM_ADDRESS),
         })
         .rpc();
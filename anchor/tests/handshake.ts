@@ -4,18 +4,31 @@ import {
   createMint,
   createAssociatedTokenAccount,
   mintTo,
+  closeAccount,
+  createAccount,
+  getMint,
   getAssociatedTokenAddressSync,
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
+  createInitializeTransferFeeConfigInstruction,
 } from "@solana/spl-token";
 import {
   PublicKey,
   SystemProgram,
   Keypair,
   Transaction,
+  Ed25519Program,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  sendAndConfirmTransaction,
 } from "@solana/web3.js";
 import { assert } from "chai";
+import { createHash } from "crypto";
 import { Handshake } from "../target/types/handshake";
 
 // PDA seed constants (must match on-chain constants)
@@ -23,6 +36,11 @@ const POOL_SEED = Buffer.from("pool");
 const SENDER_SEED = Buffer.from("sender");
 const RECIPIENT_SEED = Buffer.from("recipient");
 const NONCE_SEED = Buffer.from("nonce");
+const EXPECTATION_SEED = Buffer.from("expectation");
+
+// Batch cap and the default per-transaction compute budget it must fit in
+const MAX_BATCH_SIZE = 8;
+const DEFAULT_COMPUTE_UNITS = 200_000;
 
 // ─── Helpers ───────────────────────────────────────────────────────────────────
 
@@ -55,6 +73,17 @@ function findTransferPda(
   );
 }
 
+function findExpectationPda(
+  programId: PublicKey,
+  poolPda: PublicKey,
+  recipient: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [EXPECTATION_SEED, poolPda.toBuffer(), recipient.toBuffer()],
+    programId
+  );
+}
+
 function getAta(mint: PublicKey, owner: PublicKey): PublicKey {
   return getAssociatedTokenAddressSync(mint, owner, true);
 }
@@ -67,9 +96,19 @@ async function getTokenBalance(
   return new BN(info.value.amount);
 }
 
+/** Compute units consumed by a transaction sent with "confirmed" commitment */
+async function getComputeUnits(
+  connection: web3.Connection,
+  sig: string
+): Promise<number> {
+  const tx = await connection.getTransaction(sig, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+  return tx!.meta!.computeUnitsConsumed!;
+}
+
 /** Build and return the accounts object for createTransfer */
 function createTransferAccounts(
   sender: PublicKey,
+  recipient: PublicKey,
   poolPda: PublicKey,
   mint: PublicKey,
   transferPda: PublicKey
@@ -81,6 +120,11 @@ function createTransferAccounts(
     poolTokenAccount: getAta(mint, poolPda),
     senderTokenAccount: getAta(mint, sender),
     transfer: transferPda,
+    recipientExpectation: findExpectationPda(programId, poolPda, recipient)[0],
+    instructionsSysvar: null,
+    couponRedemption: null,
+    refundTokenAccount: null,
+    senderProfile: null,
     tokenProgram: TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -102,6 +146,7 @@ function cancelTransferAccounts(
     senderTokenAccount: getAta(mint, sender),
     transfer: transferPda,
     tokenProgram: TOKEN_PROGRAM_ID,
+    senderProfile: null,
   };
 }
 
@@ -122,6 +167,9 @@ function claimTransferAccounts(
     transfer: transferPda,
     sender,
     tokenProgram: TOKEN_PROGRAM_ID,
+    feeVault: null,
+    memoProgram: null,
+    senderTokenAccount: null,
   };
 }
 
@@ -142,6 +190,10 @@ function rejectTransferAccounts(
     transfer: transferPda,
     sender,
     tokenProgram: TOKEN_PROGRAM_ID,
+    refundTokenAccount: null,
+    pendingRefund: null,
+    systemProgram: null,
+    senderProfile: null,
   };
 }
 
@@ -162,6 +214,7 @@ function declineTransferAccounts(
     transfer: transferPda,
     sender,
     tokenProgram: TOKEN_PROGRAM_ID,
+    senderProfile: null,
   };
 }
 
@@ -181,7 +234,11 @@ function expireTransferAccounts(
     senderTokenAccount: getAta(mint, sender),
     transfer: transferPda,
     sender,
+    recipientTokenAccount: null,
+    callerTokenAccount: null,
     tokenProgram: TOKEN_PROGRAM_ID,
+    feeVault: null,
+    senderProfile: null,
   };
 }
 
@@ -202,6 +259,7 @@ function destroyTransferAccounts(
     sender,
     transfer: transferPda,
     tokenProgram: TOKEN_PROGRAM_ID,
+    senderProfile: null,
   };
 }
 
@@ -303,7 +361,7 @@ describe("handshake", () => {
   describe("A. Pool Initialization", () => {
     it("A1. initializes a pool with 0% fee", async () => {
       await program.methods
-        .initPool(zeroFeePoolId, 0)
+        .initPool(zeroFeePoolId, 0, "", "")
         .accounts({
           operator,
           mint,
@@ -334,7 +392,7 @@ describe("handshake", () => {
 
     it("A2. initializes a pool with 2.5% fee (250 bps)", async () => {
       await program.methods
-        .initPool(feePoolId, FEE_BPS)
+        .initPool(feePoolId, FEE_BPS, "Fee Pool", "https://example.com/fee-pool.json")
         .accounts({
           operator,
           mint,
@@ -358,7 +416,7 @@ describe("handshake", () => {
 
       try {
         await program.methods
-          .initPool(badPoolId, 10001)
+          .initPool(badPoolId, 10001, "", "")
           .accounts({
             operator,
             mint,
@@ -399,9 +457,16 @@ describe("handshake", () => {
           TRANSFER_AMOUNT,
           "test cancel",
           new BN(0),
+          new BN(0),
+          null,
+          new BN(0),
+          null,
+          false,
+          null,
+          null,
           new BN(0)
         )
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -452,8 +517,8 @@ describe("handshake", () => {
 
       // Create transfer
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -491,8 +556,8 @@ describe("handshake", () => {
 
       // Create transfer with short deadline
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "expire test", new BN(0), claimableUntil)
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "expire test", new BN(0), claimableUntil, null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -523,8 +588,8 @@ describe("handshake", () => {
       const claimableUntil = new BN(now + 3600); // 1 hour from now
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "not expired", new BN(0), claimableUntil)
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "not expired", new BN(0), claimableUntil, null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -552,8 +617,8 @@ describe("handshake", () => {
       const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "no deadline", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "no deadline", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -595,8 +660,8 @@ describe("handshake", () => {
 
       // Create
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "claim test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "claim test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -607,7 +672,7 @@ describe("handshake", () => {
 
       // Claim
       await program.methods
-        .claimTransfer()
+        .claimTransfer(null)
         .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
         .signers([recipient])
         .rpc();
@@ -644,15 +709,15 @@ describe("handshake", () => {
       const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth claim", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth claim", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
       // ThirdParty tries to claim
       try {
         await program.methods
-          .claimTransfer()
+          .claimTransfer(null)
           .accounts(claimTransferAccounts(thirdParty.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
           .signers([thirdParty])
           .rpc();
@@ -678,14 +743,14 @@ describe("handshake", () => {
       const claimableUntil = new BN(now + 7200); // 2 hours from now
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "early claim", claimableAfter, claimableUntil)
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "early claim", claimableAfter, claimableUntil, null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
       try {
         await program.methods
-          .claimTransfer()
+          .claimTransfer(null)
           .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
           .signers([recipient])
           .rpc();
@@ -711,8 +776,8 @@ describe("handshake", () => {
 
       // Create
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "reject test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "reject test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -748,8 +813,8 @@ describe("handshake", () => {
       const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth reject", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth reject", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -791,8 +856,8 @@ describe("handshake", () => {
 
       // Create
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "decline test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "decline test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -832,8 +897,8 @@ describe("handshake", () => {
 
       // Create
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "no reason", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "no reason", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -862,8 +927,8 @@ describe("handshake", () => {
       const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth decline", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth decline", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -893,8 +958,8 @@ describe("handshake", () => {
 
       // Create and immediately cancel
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "cancel first", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "cancel first", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -929,8 +994,8 @@ describe("handshake", () => {
 
       try {
         await program.methods
-          .createTransfer(recipient.publicKey, nonce, new BN(0), "zero amount", new BN(0), new BN(0))
-          .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+          .createTransfer(recipient.publicKey, nonce, new BN(0), "zero amount", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
           .signers([sender])
           .rpc();
         assert.fail("Should fail with zero amount");
@@ -952,9 +1017,16 @@ describe("handshake", () => {
             new BN(1_000_000),
             longMemo,
             new BN(0),
+            new BN(0),
+            null,
+            new BN(0),
+            null,
+            false,
+            null,
+            null,
             new BN(0)
           )
-          .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
           .signers([sender])
           .rpc();
         assert.fail("Should fail with long memo");
@@ -975,8 +1047,8 @@ describe("handshake", () => {
 
       try {
         await program.methods
-          .createTransfer(recipient.publicKey, nonce, new BN(1_000_000), "paused", new BN(0), new BN(0))
-          .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+          .createTransfer(recipient.publicKey, nonce, new BN(1_000_000), "paused", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
           .signers([sender])
           .rpc();
         assert.fail("Should fail when pool is paused");
@@ -1013,6 +1085,7 @@ describe("handshake", () => {
           poolTokenAccount: getAta(mint, feePoolPda),
           operatorTokenAccount: getAta(mint, operator),
           tokenProgram: TOKEN_PROGRAM_ID,
+          feeVault: null,
         })
         .rpc();
 
@@ -1035,13 +1108,13 @@ describe("handshake", () => {
       const amount = new BN(1000 * 1_000_000);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, amount, "fee gen", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, amount, "fee gen", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
       await program.methods
-        .claimTransfer()
+        .claimTransfer(null)
         .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
         .signers([recipient])
         .rpc();
@@ -1057,6 +1130,7 @@ describe("handshake", () => {
             poolTokenAccount: getAta(mint, feePoolPda),
             operatorTokenAccount: getAta(mint, sender.publicKey),
             tokenProgram: TOKEN_PROGRAM_ID,
+            feeVault: null,
           })
           .signers([sender])
           .rpc();
@@ -1075,6 +1149,7 @@ describe("handshake", () => {
           poolTokenAccount: getAta(mint, feePoolPda),
           operatorTokenAccount: getAta(mint, operator),
           tokenProgram: TOKEN_PROGRAM_ID,
+          feeVault: null,
         })
         .rpc();
     });
@@ -1093,8 +1168,8 @@ describe("handshake", () => {
 
       // Create transfer on zero-fee pool
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "destroy test", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "destroy test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -1135,8 +1210,8 @@ describe("handshake", () => {
       const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "not paused", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "not paused", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -1163,8 +1238,8 @@ describe("handshake", () => {
       const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth destroy", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "auth destroy", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -1246,15 +1321,15 @@ describe("handshake", () => {
       const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, new BN(100 * 1_000_000), "reset block", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, new BN(100 * 1_000_000), "reset block", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
       try {
         await program.methods
           .resetPool()
-          .accounts({ operator, pool: zeroFeePoolPda })
+          .accounts({ operator, pool: zeroFeePoolPda, feeVault: null })
           .rpc();
         assert.fail("Should fail with outstanding transfers");
       } catch (err: any) {
@@ -1279,7 +1354,7 @@ describe("handshake", () => {
 
       await program.methods
         .resetPool()
-        .accounts({ operator, pool: zeroFeePoolPda })
+        .accounts({ operator, pool: zeroFeePoolPda, feeVault: null })
         .rpc();
 
       pool = await program.account.pool.fetch(zeroFeePoolPda);
@@ -1302,8 +1377,8 @@ describe("handshake", () => {
       const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
 
       await program.methods
-        .createTransfer(recipient.publicKey, nonce, new BN(100 * 1_000_000), "close block", new BN(0), new BN(0))
-        .accounts(createTransferAccounts(sender.publicKey, zeroFeePoolPda, mint, transferPda))
+        .createTransfer(recipient.publicKey, nonce, new BN(100 * 1_000_000), "close block", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, zeroFeePoolPda, mint, transferPda))
         .signers([sender])
         .rpc();
 
@@ -1316,6 +1391,8 @@ describe("handshake", () => {
             mint,
             poolTokenAccount: getAta(mint, zeroFeePoolPda),
             operatorTokenAccount: getAta(mint, operator),
+            feeVault: null,
+            stakeVault: null,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
           .rpc();
@@ -1345,6 +1422,8 @@ describe("handshake", () => {
           mint,
           poolTokenAccount: getAta(mint, zeroFeePoolPda),
           operatorTokenAccount: getAta(mint, operator),
+          feeVault: null,
+          stakeVault: null,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc();
@@ -1363,4 +1442,4142 @@ describe("handshake", () => {
       }
     });
   });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group J: Fee Burn
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("J. Fee Burn", () => {
+    const TRANSFER_AMOUNT = new BN(10_000 * 1_000_000); // 10,000 tokens
+    const EXPECTED_FEE = new BN(250 * 1_000_000); // 2.5% of 10,000 = 250 tokens
+    const BURN_BPS = 4000; // 40% of the fee
+    const EXPECTED_BURN = new BN(100 * 1_000_000); // 40% of 250 = 100 tokens
+
+    it("J1. burns the configured share of the fee on claim", async () => {
+      await program.methods
+        .setFeeBurnBps(BURN_BPS)
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      let pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.feeBurnBps, BURN_BPS);
+
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "burn test", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      const supplyBefore = (await getMint(connection, mint)).supply;
+      const feesBefore = pool.collectedFees;
+
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+
+      // Burned share leaves the mint supply
+      const supplyAfter = (await getMint(connection, mint)).supply;
+      assert.equal((supplyBefore - supplyAfter).toString(), EXPECTED_BURN.toString());
+
+      // Remaining share is collected
+      pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(
+        pool.collectedFees.sub(feesBefore).toString(),
+        EXPECTED_FEE.sub(EXPECTED_BURN).toString()
+      );
+
+      // Reset for subsequent tests
+      await program.methods
+        .setFeeBurnBps(0)
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+
+    it("J2. fails to set fee burn > 10000 bps", async () => {
+      try {
+        await program.methods
+          .setFeeBurnBps(10001)
+          .accounts({ operator, pool: feePoolPda })
+          .rpc();
+        assert.fail("Should fail with invalid fee config");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidFeeConfig");
+      }
+    });
+
+    it("J3. fails when non-operator tries to set fee burn", async () => {
+      try {
+        await program.methods
+          .setFeeBurnBps(BURN_BPS)
+          .accounts({ operator: sender.publicKey, pool: feePoolPda })
+          .signers([sender])
+          .rpc();
+        assert.fail("Non-operator should not be able to set fee burn");
+      } catch (err: any) {
+        assert.ok(err);
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group K: Transfer Fee Changes
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("K. Transfer Fee Changes", () => {
+    const FEE_CHANGE_COOLDOWN = 24 * 60 * 60;
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+    let feeChangePoolPda: PublicKey;
+
+    before(async () => {
+      const poolId = Keypair.generate().publicKey;
+      [feeChangePoolPda] = findPoolPda(programId, poolId);
+      await program.methods
+        .initPool(poolId, FEE_BPS, "", "")
+        .accounts({
+          operator,
+          mint,
+          pool: feeChangePoolPda,
+          poolTokenAccount: getAta(mint, feeChangePoolPda),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    });
+
+    it("K1. a new pool's first fee change applies immediately", async () => {
+      let pool = await program.account.pool.fetch(feeChangePoolPda);
+      assert.equal(pool.feeChangeCooldown.toNumber(), FEE_CHANGE_COOLDOWN);
+      assert.equal(pool.lastFeeChangeAt.toNumber(), 0);
+
+      await program.methods
+        .setTransferFeeBps(100)
+        .accounts({ operator, pool: feeChangePoolPda })
+        .rpc();
+
+      pool = await program.account.pool.fetch(feeChangePoolPda);
+      assert.equal(pool.transferFeeBps, 100);
+      assert.isTrue(pool.lastFeeChangeAt.gt(new BN(0)));
+    });
+
+    it("K2. fails to change the fee within the cooldown", async () => {
+      try {
+        await program.methods
+          .setTransferFeeBps(200)
+          .accounts({ operator, pool: feeChangePoolPda })
+          .rpc();
+        assert.fail("Should fail within fee change cooldown");
+      } catch (err: any) {
+        assert.include(err.toString(), "FeeChangeCooldown");
+      }
+
+      const pool = await program.account.pool.fetch(feeChangePoolPda);
+      assert.equal(pool.transferFeeBps, 100);
+    });
+
+    it("K3. fails when non-operator tries to change the fee", async () => {
+      try {
+        await program.methods
+          .setTransferFeeBps(0)
+          .accounts({ operator: sender.publicKey, pool: feePoolPda })
+          .signers([sender])
+          .rpc();
+        assert.fail("Non-operator should not be able to change the fee");
+      } catch (err: any) {
+        assert.ok(err);
+      }
+    });
+
+    it("K4. operator sets the cooldown within its bounds", async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, feeChangeCooldown: new BN(2 * 60 * 60) })
+        .accounts({ operator, pool: feeChangePoolPda })
+        .rpc();
+      const pool = await program.account.pool.fetch(feeChangePoolPda);
+      assert.equal(pool.feeChangeCooldown.toNumber(), 2 * 60 * 60);
+
+      for (const cooldown of [new BN(0), new BN(31 * 24 * 60 * 60)]) {
+        try {
+          await program.methods
+            .updatePoolConfig({ ...unchanged, feeChangeCooldown: cooldown })
+            .accounts({ operator, pool: feeChangePoolPda })
+            .rpc();
+          assert.fail("Cooldown outside its bounds should be rejected");
+        } catch (err: any) {
+          assert.include(err.toString(), "InvalidTimeWindow");
+        }
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group L: Claim Codes
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("L. Claim Codes", () => {
+    const TRANSFER_AMOUNT = new BN(100 * 1_000_000);
+
+    function claimCodeHash(transferPda: PublicKey, code: Buffer): number[] {
+      return Array.from(
+        createHash("sha256").update(transferPda.toBuffer()).update(code).digest()
+      );
+    }
+
+    it("L1. recipient claims with the correct claim code", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      const code = Keypair.generate().publicKey.toBuffer();
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "code test", new BN(0), new BN(0), claimCodeHash(transferPda, code), new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.deepEqual(escrow.claimCodeHash, claimCodeHash(transferPda, code));
+
+      await program.methods
+        .claimTransfer(Array.from(code))
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+
+      const closed = await connection.getAccountInfo(transferPda);
+      assert.isNull(closed);
+    });
+
+    it("L2. fails to claim with a missing or wrong claim code", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      const code = Keypair.generate().publicKey.toBuffer();
+      const wrongCode = Keypair.generate().publicKey.toBuffer();
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "bad code", new BN(0), new BN(0), claimCodeHash(transferPda, code), new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      for (const attempt of [null, Array.from(wrongCode)]) {
+        try {
+          await program.methods
+            .claimTransfer(attempt)
+            .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+            .signers([recipient])
+            .rpc();
+          assert.fail("Should fail without the correct claim code");
+        } catch (err: any) {
+          assert.include(err.toString(), "InvalidClaimCode");
+        }
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group M: Batch Fee Withdrawal
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("M. Batch Fee Withdrawal", () => {
+    const TRANSFER_AMOUNT = new BN(1000 * 1_000_000);
+    let secondPoolId: PublicKey;
+    let secondPoolPda: PublicKey;
+
+    function poolRemainingAccounts(pools: PublicKey[]) {
+      return pools.flatMap((poolPda) => [
+        { pubkey: poolPda, isWritable: true, isSigner: false },
+        { pubkey: getAta(mint, poolPda), isWritable: true, isSigner: false },
+      ]);
+    }
+
+    before(async () => {
+      secondPoolId = Keypair.generate().publicKey;
+      [secondPoolPda] = findPoolPda(programId, secondPoolId);
+
+      await program.methods
+        .initPool(secondPoolId, FEE_BPS, "", "")
+        .accounts({
+          operator,
+          mint,
+          pool: secondPoolPda,
+          poolTokenAccount: getAta(mint, secondPoolPda),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+
+      // Generate fees in both pools
+      for (const poolPda of [feePoolPda, secondPoolPda]) {
+        const nonce = nextNonce();
+        const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+
+        await program.methods
+          .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "batch fees", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, poolPda, mint, transferPda))
+          .signers([sender])
+          .rpc();
+
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, poolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+      }
+    });
+
+    it("M1. operator sweeps fees from multiple pools into one treasury", async () => {
+      const feesA = (await program.account.pool.fetch(feePoolPda)).collectedFees;
+      const feesB = (await program.account.pool.fetch(secondPoolPda)).collectedFees;
+      assert.isTrue(feesA.gt(new BN(0)) && feesB.gt(new BN(0)));
+
+      const treasuryBalBefore = await getTokenBalance(connection, getAta(mint, operator));
+
+      await program.methods
+        .batchWithdrawFees()
+        .accounts({
+          operator,
+          mint,
+          treasuryTokenAccount: getAta(mint, operator),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(poolRemainingAccounts([feePoolPda, secondPoolPda]))
+        .rpc();
+
+      const treasuryBalAfter = await getTokenBalance(connection, getAta(mint, operator));
+      assert.equal(
+        treasuryBalAfter.sub(treasuryBalBefore).toString(),
+        feesA.add(feesB).toString()
+      );
+
+      assert.equal((await program.account.pool.fetch(feePoolPda)).collectedFees.toNumber(), 0);
+      assert.equal((await program.account.pool.fetch(secondPoolPda)).collectedFees.toNumber(), 0);
+    });
+
+    it("M2. fails when non-operator sweeps fees", async () => {
+      try {
+        await program.methods
+          .batchWithdrawFees()
+          .accounts({
+            operator: sender.publicKey,
+            mint,
+            treasuryTokenAccount: getAta(mint, sender.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .remainingAccounts(poolRemainingAccounts([feePoolPda]))
+          .signers([sender])
+          .rpc();
+        assert.fail("Non-operator should not be able to sweep fees");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+
+    it("M3. fails when the batch exceeds the maximum size", async () => {
+      try {
+        await program.methods
+          .batchWithdrawFees()
+          .accounts({
+            operator,
+            mint,
+            treasuryTokenAccount: getAta(mint, operator),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .remainingAccounts(poolRemainingAccounts(Array(MAX_BATCH_SIZE + 1).fill(feePoolPda)))
+          .rpc();
+        assert.fail("Should fail with batch too large");
+      } catch (err: any) {
+        assert.include(err.toString(), "BatchTooLarge");
+      }
+    });
+
+    it("M4. a full batch fits the default compute budget", async () => {
+      // One pool swept alone, then MAX_BATCH_SIZE more swept together
+      const pools: PublicKey[] = [];
+      for (let i = 0; i <= MAX_BATCH_SIZE; i++) {
+        const poolId = Keypair.generate().publicKey;
+        const [poolPda] = findPoolPda(programId, poolId);
+        await program.methods
+          .initPool(poolId, FEE_BPS, "", "")
+          .accounts({
+            operator,
+            mint,
+            pool: poolPda,
+            poolTokenAccount: getAta(mint, poolPda),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .rpc();
+
+        const nonce = nextNonce();
+        const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+        await program.methods
+          .createTransfer(recipient.publicKey, nonce, new BN(10 * 1_000_000), "batch fees", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, poolPda, mint, transferPda))
+          .signers([sender])
+          .rpc();
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, poolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+        pools.push(poolPda);
+      }
+
+      const sweep = async (batch: PublicKey[]) =>
+        getComputeUnits(
+          connection,
+          await program.methods
+            .batchWithdrawFees()
+            .accounts({
+              operator,
+              mint,
+              treasuryTokenAccount: getAta(mint, operator),
+              tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(poolRemainingAccounts(batch))
+            .rpc({ commitment: "confirmed" })
+        );
+      const single = await sweep(pools.slice(0, 1));
+      const full = await sweep(pools.slice(1));
+
+      const perItem = (full - single) / (MAX_BATCH_SIZE - 1);
+      assert.isBelow(full, DEFAULT_COMPUTE_UNITS);
+      assert.isBelow(perItem, DEFAULT_COMPUTE_UNITS / MAX_BATCH_SIZE);
+    });
+
+    it("M5. without a registered fee account the treasury must be the operator's ATA", async () => {
+      assert.isTrue((await program.account.pool.fetch(feePoolPda)).operatorFeeAccount.equals(PublicKey.default));
+      try {
+        await program.methods
+          .batchWithdrawFees()
+          .accounts({
+            operator,
+            mint,
+            treasuryTokenAccount: getAta(mint, sender.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .remainingAccounts(poolRemainingAccounts([feePoolPda]))
+          .rpc();
+        assert.fail("Fees should not be swept to an arbitrary account");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidFeeAccount");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group N: Recipient Expectations
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("N. Recipient Expectations", () => {
+    const EXPECTED_AMOUNT = new BN(50 * 1_000_000);
+
+    it("N1. recipient sets an expected amount", async () => {
+      const [expectationPda] = findExpectationPda(programId, feePoolPda, recipient.publicKey);
+
+      await program.methods
+        .setRecipientExpectation(EXPECTED_AMOUNT)
+        .accounts({
+          recipient: recipient.publicKey,
+          pool: feePoolPda,
+          recipientExpectation: expectationPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([recipient])
+        .rpc();
+
+      const expectation = await program.account.recipientExpectation.fetch(expectationPda);
+      assert.ok(expectation.recipient.equals(recipient.publicKey));
+      assert.ok(expectation.pool.equals(feePoolPda));
+      assert.equal(expectation.expectedAmount.toNumber(), EXPECTED_AMOUNT.toNumber());
+    });
+
+    it("N2. fails to create a transfer below the expected amount", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+
+      try {
+        await program.methods
+          .createTransfer(recipient.publicKey, nonce, EXPECTED_AMOUNT.subn(1), "too small", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+          .signers([sender])
+          .rpc();
+        assert.fail("Should fail below the expected amount");
+      } catch (err: any) {
+        assert.include(err.toString(), "AmountBelowExpected");
+      }
+    });
+
+    it("N3. creates a transfer at the expected amount", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, EXPECTED_AMOUNT, "exact", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.amount.toNumber(), EXPECTED_AMOUNT.toNumber());
+
+      // Cleanup
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("N4. recipient closes the expectation", async () => {
+      const [expectationPda] = findExpectationPda(programId, feePoolPda, recipient.publicKey);
+
+      await program.methods
+        .closeRecipientExpectation()
+        .accounts({
+          recipient: recipient.publicKey,
+          pool: feePoolPda,
+          recipientExpectation: expectationPda,
+        })
+        .signers([recipient])
+        .rpc();
+
+      const closed = await connection.getAccountInfo(expectationPda);
+      assert.isNull(closed);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group O: Refund Floor
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("O. Refund Floor", () => {
+    const TRANSFER_AMOUNT = new BN(100 * 1_000_000);
+    // MIN_REFUND_BPS = 9000 → at most 10% can ever be taken as a fee
+    const FLOOR_NET = TRANSFER_AMOUNT.muln(9000).divn(10000);
+    let greedyPoolId: PublicKey;
+    let greedyPoolPda: PublicKey;
+
+    before(async () => {
+      greedyPoolId = Keypair.generate().publicKey;
+      [greedyPoolPda] = findPoolPda(programId, greedyPoolId);
+
+      await program.methods
+        .initPool(greedyPoolId, 10000, "", "")
+        .accounts({
+          operator,
+          mint,
+          pool: greedyPoolPda,
+          poolTokenAccount: getAta(mint, greedyPoolPda),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    });
+
+    it("O1. a 100% configured fee still leaves the recipient the floor", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      const recipientBalBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "greedy fee", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, greedyPoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, greedyPoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+
+      const recipientBalAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(
+        recipientBalAfter.sub(recipientBalBefore).toString(),
+        FLOOR_NET.toString(),
+        "Fee should be capped at 10% of the transfer"
+      );
+
+      const pool = await program.account.pool.fetch(greedyPoolPda);
+      assert.equal(pool.collectedFees.toString(), TRANSFER_AMOUNT.sub(FLOOR_NET).toString());
+    });
+
+    it("O2. a rejected transfer on the same pool refunds the sender in full", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "greedy reject", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, greedyPoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      await program.methods
+        .rejectTransfer(1)
+        .accounts(rejectTransferAccounts(operator, sender.publicKey, greedyPoolPda, mint, transferPda))
+        .rpc();
+
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(senderBalAfter.toString(), senderBalBefore.toString());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group P: Relayed Operator Resolution
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("P. Relayed Operator Resolution", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    const REASON = 3;
+
+    function rejectMessage(transferPda: PublicKey, operatorNonce: BN, reason: number): Buffer {
+      return Buffer.concat([
+        Buffer.from("handshake:reject_transfer"),
+        transferPda.toBuffer(),
+        operatorNonce.toArrayLike(Buffer, "le", 8),
+        Buffer.from([1, reason]),
+      ]);
+    }
+
+    async function createActiveTransfer(): Promise<PublicKey> {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "relayed", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+      return transferPda;
+    }
+
+    /** Submit a relayed reject with thirdParty as relayer and fee payer */
+    async function relayReject(transferPda: PublicKey, operatorNonce: BN, signer: Keypair, refundTokenAccount: PublicKey | null = null) {
+      const verifyIx = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: rejectMessage(transferPda, operatorNonce, REASON),
+      });
+      const rejectIx = await program.methods
+        .rejectTransferBySignature(REASON, operatorNonce)
+        .accounts({
+          relayer: thirdParty.publicKey,
+          pool: feePoolPda,
+          mint,
+          poolTokenAccount: getAta(mint, feePoolPda),
+          senderTokenAccount: getAta(mint, sender.publicKey),
+          refundTokenAccount,
+          pendingRefund: null,
+          transfer: transferPda,
+          sender: sender.publicKey,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+          senderProfile: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: null,
+        })
+        .instruction();
+
+      const tx = new Transaction().add(verifyIx, rejectIx);
+      tx.feePayer = thirdParty.publicKey;
+      await sendAndConfirmTransaction(connection, tx, [thirdParty]);
+    }
+
+    function errorText(err: any): string {
+      return err.toString() + (err.logs ?? []).join("\n");
+    }
+
+    it("P1. relayer rejects a transfer with the operator's signature", async () => {
+      const transferPda = await createActiveTransfer();
+      const before = await program.account.pool.fetch(feePoolPda);
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      const operatorLamportsBefore = await connection.getBalance(operator);
+
+      await relayReject(transferPda, before.operatorNonce, payerKeypair);
+
+      const closed = await connection.getAccountInfo(transferPda);
+      assert.isNull(closed);
+
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(senderBalAfter.sub(senderBalBefore).toString(), TRANSFER_AMOUNT.toString());
+
+      const after = await program.account.pool.fetch(feePoolPda);
+      assert.equal(after.operatorNonce.toNumber(), before.operatorNonce.toNumber() + 1);
+
+      // Operator paid nothing
+      assert.equal(await connection.getBalance(operator), operatorLamportsBefore);
+    });
+
+    it("P2. fails when the operator nonce is stale", async () => {
+      const transferPda = await createActiveTransfer();
+      const pool = await program.account.pool.fetch(feePoolPda);
+
+      try {
+        await relayReject(transferPda, pool.operatorNonce.subn(1), payerKeypair);
+        assert.fail("Stale nonce should be rejected");
+      } catch (err: any) {
+        assert.include(errorText(err), "NonceAlreadyUsed");
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("P3. fails when the message is not signed by the operator", async () => {
+      const transferPda = await createActiveTransfer();
+      const pool = await program.account.pool.fetch(feePoolPda);
+
+      try {
+        await relayReject(transferPda, pool.operatorNonce, thirdParty);
+        assert.fail("Non-operator signature should be rejected");
+      } catch (err: any) {
+        assert.include(errorText(err), "InvalidSignature");
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("P4. fails when the operator nonce skips ahead", async () => {
+      const transferPda = await createActiveTransfer();
+      const pool = await program.account.pool.fetch(feePoolPda);
+
+      try {
+        await relayReject(transferPda, pool.operatorNonce.addn(1), payerKeypair);
+        assert.fail("Future nonce should be rejected");
+      } catch (err: any) {
+        assert.include(errorText(err), "InvalidOperatorNonce");
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("P5. a relayed reject refunds to the transfer's refund override", async () => {
+      const vault = await createAccount(connection, payerKeypair, mint, thirdParty.publicKey, Keypair.generate());
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "relayed vault", new BN(0), new BN(0), null, new BN(0), vault, false, null, null, new BN(0))
+        .accounts({
+          ...createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda),
+          refundTokenAccount: vault,
+        })
+        .signers([sender])
+        .rpc();
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      try {
+        await relayReject(transferPda, pool.operatorNonce, payerKeypair);
+        assert.fail("Relayed reject should require the refund override account");
+      } catch (err: any) {
+        assert.include(errorText(err), "InvalidRefundAccount");
+      }
+
+      await relayReject(transferPda, pool.operatorNonce, payerKeypair, vault);
+      assert.equal((await getTokenBalance(connection, vault)).toString(), TRANSFER_AMOUNT.toString());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group Q: Keeper Tips
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("Q. Keeper Tips", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    const KEEPER_TIP = new BN(100_000);
+
+    it("Q1. keeper receives the tip when expiring a transfer", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      const claimableUntil = new BN(Math.floor(Date.now() / 1000) + 3);
+
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      const keeperBalBefore = await getTokenBalance(connection, getAta(mint, thirdParty.publicKey));
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "tipped", new BN(0), claimableUntil, null, KEEPER_TIP, null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.keeperTip.toString(), KEEPER_TIP.toString());
+
+      // Wait for expiry (generous margin for validator clock lag)
+      await new Promise((resolve) => setTimeout(resolve, 6000));
+
+      await program.methods
+        .expireTransfer()
+        .accounts({
+          ...expireTransferAccounts(thirdParty.publicKey, sender.publicKey, feePoolPda, mint, transferPda),
+          callerTokenAccount: getAta(mint, thirdParty.publicKey),
+        })
+        .signers([thirdParty])
+        .rpc();
+
+      const keeperBalAfter = await getTokenBalance(connection, getAta(mint, thirdParty.publicKey));
+      assert.equal(keeperBalAfter.sub(keeperBalBefore).toString(), KEEPER_TIP.toString());
+
+      // Sender is refunded everything but the tip
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(senderBalBefore.sub(senderBalAfter).toString(), KEEPER_TIP.toString());
+    });
+
+    it("Q2. fails to create a transfer whose tip consumes the amount", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+
+      try {
+        await program.methods
+          .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "all tip", new BN(0), new BN(0), null, TRANSFER_AMOUNT, null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+          .signers([sender])
+          .rpc();
+        assert.fail("Should fail with tip >= amount");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidKeeperTip");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group R: Canonical Pools
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("R. Canonical Pools", () => {
+    const CANONICAL_POOL_SEED = Buffer.from("canonical_pool");
+    function findCanonicalPoolPda(): PublicKey {
+      return PublicKey.findProgramAddressSync(
+        [CANONICAL_POOL_SEED, operator.toBuffer(), mint.toBuffer()],
+        programId
+      )[0];
+    }
+
+    function initCanonicalPoolAccounts(poolPda: PublicKey) {
+      return {
+        operator,
+        mint,
+        canonicalPool: findCanonicalPoolPda(),
+        pool: poolPda,
+        poolTokenAccount: getAta(mint, poolPda),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      };
+    }
+
+    it("R1. operator creates the canonical pool for a mint", async () => {
+      const poolId = Keypair.generate().publicKey;
+      const [poolPda] = findPoolPda(programId, poolId);
+
+      await program.methods
+        .initCanonicalPool(poolId, FEE_BPS, "", "")
+        .accounts(initCanonicalPoolAccounts(poolPda))
+        .rpc();
+
+      const marker = await program.account.canonicalPool.fetch(findCanonicalPoolPda());
+      assert.ok(marker.operator.equals(operator));
+      assert.ok(marker.mint.equals(mint));
+      assert.ok(marker.pool.equals(poolPda));
+
+      const pool = await program.account.pool.fetch(poolPda);
+      assert.ok(pool.poolId.equals(poolId));
+      assert.equal(pool.transferFeeBps, FEE_BPS);
+    });
+
+    it("R2. fails to create a second canonical pool for the same mint", async () => {
+      const poolId = Keypair.generate().publicKey;
+      const [poolPda] = findPoolPda(programId, poolId);
+
+      try {
+        await program.methods
+          .initCanonicalPool(poolId, FEE_BPS, "", "")
+          .accounts(initCanonicalPoolAccounts(poolPda))
+          .rpc();
+        assert.fail("Canonical pool should only be created once");
+      } catch (err: any) {
+        assert.include(err.toString() + (err.logs ?? []).join("\n"), "already in use");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group S: Sealed Amounts (commit-reveal)
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("S. Sealed Amounts", () => {
+    const MAX_AMOUNT = new BN(100 * 1_000_000);
+    const REAL_AMOUNT = new BN(60 * 1_000_000);
+
+    function amountCommitment(amount: BN, salt: Buffer): number[] {
+      return Array.from(
+        createHash("sha256").update(amount.toArrayLike(Buffer, "le", 8)).update(salt).digest()
+      );
+    }
+
+    function revealTransferAccounts(revealer: PublicKey, transferPda: PublicKey) {
+      return {
+        revealer,
+        pool: feePoolPda,
+        mint,
+        poolTokenAccount: getAta(mint, feePoolPda),
+        senderTokenAccount: getAta(mint, sender.publicKey),
+        transfer: transferPda,
+        recipientExpectation: findExpectationPda(programId, feePoolPda, recipient.publicKey)[0],
+        tokenProgram: TOKEN_PROGRAM_ID,
+      };
+    }
+
+    async function createSealed(salt: Buffer): Promise<PublicKey> {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransferCommitted(recipient.publicKey, nonce, MAX_AMOUNT, amountCommitment(REAL_AMOUNT, salt), "sealed", new BN(0), new BN(0), new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+      return transferPda;
+    }
+
+    it("S1. sealed transfer is claimable only after reveal, on the revealed amount", async () => {
+      const salt = Keypair.generate().publicKey.toBuffer();
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      const transferPda = await createSealed(salt);
+
+      let escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.amount.toString(), MAX_AMOUNT.toString());
+
+      try {
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+        assert.fail("Should not claim before reveal");
+      } catch (err: any) {
+        assert.include(err.toString(), "AmountNotRevealed");
+      }
+
+      await program.methods
+        .revealTransfer(REAL_AMOUNT, Array.from(salt))
+        .accounts(revealTransferAccounts(sender.publicKey, transferPda))
+        .signers([sender])
+        .rpc();
+
+      escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.amount.toString(), REAL_AMOUNT.toString());
+      assert.isNull(escrow.amountCommitment);
+
+      // Excess refunded to sender
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(senderBalBefore.sub(senderBalAfter).toString(), REAL_AMOUNT.toString());
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      const expectedNet = REAL_AMOUNT.sub(REAL_AMOUNT.muln(pool.transferFeeBps).divn(10000));
+      const recipientBalBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+
+      const recipientBalAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), expectedNet.toString());
+    });
+
+    it("S2. fails to reveal with the wrong salt", async () => {
+      const salt = Keypair.generate().publicKey.toBuffer();
+      const transferPda = await createSealed(salt);
+
+      try {
+        await program.methods
+          .revealTransfer(REAL_AMOUNT, Array.from(Keypair.generate().publicKey.toBuffer()))
+          .accounts(revealTransferAccounts(recipient.publicKey, transferPda))
+          .signers([recipient])
+          .rpc();
+        assert.fail("Should fail with a mismatched reveal");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidAmountReveal");
+      }
+
+      // Cleanup: cancelling refunds the full escrowed maximum
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("S3. milestones cannot be set before the amount is revealed", async () => {
+      const salt = Keypair.generate().publicKey.toBuffer();
+      const transferPda = await createSealed(salt);
+
+      try {
+        await program.methods
+          .setMilestones([5000, 5000])
+          .accounts({ sender: sender.publicKey, pool: feePoolPda, transfer: transferPda })
+          .signers([sender])
+          .rpc();
+        assert.fail("Sealed transfer should not be staged before reveal");
+      } catch (err: any) {
+        assert.include(err.toString(), "AmountNotRevealed");
+      }
+
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group T: Settlement
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("T. Settlement", () => {
+    const TRANSFER_AMOUNT = new BN(100 * 1_000_000);
+
+    function settleTransferAccounts(operatorKey: PublicKey, transferPda: PublicKey) {
+      return {
+        operator: operatorKey,
+        pool: feePoolPda,
+        mint,
+        poolTokenAccount: getAta(mint, feePoolPda),
+        recipientTokenAccount: getAta(mint, recipient.publicKey),
+        senderTokenAccount: getAta(mint, sender.publicKey),
+        transfer: transferPda,
+        sender: sender.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        feeVault: null,
+      };
+    }
+
+    async function createActiveTransfer(): Promise<PublicKey> {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "settle", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+      return transferPda;
+    }
+
+    it("T1. operator splits a transfer between recipient and sender", async () => {
+      const toRecipient = new BN(40 * 1_000_000);
+      const transferPda = await createActiveTransfer();
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      const fee = toRecipient.muln(pool.transferFeeBps).divn(10000);
+      const recipientBalBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+
+      await program.methods
+        .settleTransfer(toRecipient)
+        .accounts(settleTransferAccounts(operator, transferPda))
+        .rpc();
+
+      const closed = await connection.getAccountInfo(transferPda);
+      assert.isNull(closed);
+
+      const recipientBalAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), toRecipient.toString());
+
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(
+        senderBalAfter.sub(senderBalBefore).toString(),
+        TRANSFER_AMOUNT.sub(toRecipient).sub(fee).toString()
+      );
+    });
+
+    it("T2. fails when the recipient share plus fee exceeds the amount", async () => {
+      const transferPda = await createActiveTransfer();
+
+      try {
+        await program.methods
+          .settleTransfer(TRANSFER_AMOUNT)
+          .accounts(settleTransferAccounts(operator, transferPda))
+          .rpc();
+        assert.fail("Should fail when the split exceeds the amount");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidSettlement");
+      }
+
+      try {
+        await program.methods
+          .settleTransfer(new BN(0))
+          .accounts(settleTransferAccounts(sender.publicKey, transferPda))
+          .signers([sender])
+          .rpc();
+        assert.fail("Non-operator should not settle");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group U: Deferred Refunds
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("U. Deferred Refunds", () => {
+    const TRANSFER_AMOUNT = new BN(5 * 1_000_000);
+    const REFUND_SEED = Buffer.from("refund");
+    let lonelySender: Keypair;
+    let transferPda: PublicKey;
+    let pendingRefundPda: PublicKey;
+
+    function deferredRejectAccounts() {
+      return {
+        ...rejectTransferAccounts(operator, lonelySender.publicKey, feePoolPda, mint, transferPda),
+        pendingRefund: pendingRefundPda,
+        systemProgram: SystemProgram.programId,
+      };
+    }
+
+    before(async () => {
+      lonelySender = Keypair.generate();
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: operator,
+            toPubkey: lonelySender.publicKey,
+            lamports: 0.05 * web3.LAMPORTS_PER_SOL,
+          })
+        )
+      );
+
+      const ata = await createAssociatedTokenAccount(connection, payerKeypair, mint, lonelySender.publicKey);
+      await mintTo(connection, payerKeypair, mint, ata, payerKeypair, TRANSFER_AMOUNT.toNumber());
+
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, lonelySender.publicKey, recipient.publicKey, nonce);
+      [pendingRefundPda] = PublicKey.findProgramAddressSync([REFUND_SEED, transferPda.toBuffer()], programId);
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "no ata", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(lonelySender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([lonelySender])
+        .rpc();
+
+      // Sender closes their (now empty) token account
+      await closeAccount(connection, payerKeypair, ata, lonelySender.publicKey, lonelySender);
+    });
+
+    it("U1. fails to reject to a missing token account when deferral is disabled", async () => {
+      try {
+        await program.methods
+          .rejectTransfer(1)
+          .accounts(deferredRejectAccounts())
+          .rpc();
+        assert.fail("Should fail with deferral disabled");
+      } catch (err: any) {
+        assert.include(err.toString(), "RefundDeferralDisabled");
+      }
+    });
+
+    it("U2. operator rejects and records a pending refund", async () => {
+      await program.methods
+        .setDeferMissingRefunds(true)
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      await program.methods
+        .rejectTransfer(1)
+        .accounts(deferredRejectAccounts())
+        .rpc();
+
+      const closed = await connection.getAccountInfo(transferPda);
+      assert.isNull(closed);
+
+      const refund = await program.account.pendingRefund.fetch(pendingRefundPda);
+      assert.ok(refund.sender.equals(lonelySender.publicKey));
+      assert.equal(refund.amount.toString(), TRANSFER_AMOUNT.toString());
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.pendingRefunds.toNumber(), 1);
+    });
+
+    it("U3. anyone pays out the refund once the sender has a token account", async () => {
+      const ata = await createAssociatedTokenAccount(connection, payerKeypair, mint, lonelySender.publicKey);
+
+      await program.methods
+        .claimRefund()
+        .accounts({
+          caller: thirdParty.publicKey,
+          pool: feePoolPda,
+          mint,
+          poolTokenAccount: getAta(mint, feePoolPda),
+          senderTokenAccount: ata,
+          pendingRefund: pendingRefundPda,
+          operator,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([thirdParty])
+        .rpc();
+
+      const balance = await getTokenBalance(connection, ata);
+      assert.equal(balance.toString(), TRANSFER_AMOUNT.toString());
+
+      const closed = await connection.getAccountInfo(pendingRefundPda);
+      assert.isNull(closed);
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.pendingRefunds.toNumber(), 0);
+    });
+
+    it("U4. a refund the sender's token account can receive is not deferred", async () => {
+      const nonce = nextNonce();
+      const [livePda] = findTransferPda(programId, lonelySender.publicKey, recipient.publicKey, nonce);
+      const [livePendingPda] = PublicKey.findProgramAddressSync([REFUND_SEED, livePda.toBuffer()], programId);
+      await mintTo(connection, payerKeypair, mint, getAta(mint, lonelySender.publicKey), payerKeypair, TRANSFER_AMOUNT.toNumber());
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "has ata", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(lonelySender.publicKey, recipient.publicKey, feePoolPda, mint, livePda))
+        .signers([lonelySender])
+        .rpc();
+
+      try {
+        await program.methods
+          .rejectTransfer(1)
+          .accounts({
+            ...rejectTransferAccounts(operator, lonelySender.publicKey, feePoolPda, mint, livePda),
+            pendingRefund: livePendingPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        assert.fail("Refund to a live token account should not be deferred");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidPendingRefund");
+      }
+
+      await program.methods
+        .rejectTransfer(1)
+        .accounts(rejectTransferAccounts(operator, lonelySender.publicKey, feePoolPda, mint, livePda))
+        .rpc();
+      const balance = await getTokenBalance(connection, getAta(mint, lonelySender.publicKey));
+      assert.equal(balance.toString(), TRANSFER_AMOUNT.muln(2).toString());
+
+      await program.methods
+        .setDeferMissingRefunds(false)
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group V: Pool Stats
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("V. Pool Stats", () => {
+    it("V1. reports resolution latency consistent with the pool account", async () => {
+      const stats = await program.methods
+        .getPoolStats()
+        .accounts({ pool: feePoolPda })
+        .view();
+      const pool = await program.account.pool.fetch(feePoolPda);
+
+      assert.equal(stats.totalTransfersResolved.toString(), pool.totalTransfersResolved.toString());
+      assert.equal(stats.totalResolutionTime.toString(), pool.totalResolutionTime.toString());
+      assert.isAbove(pool.totalResolutionTime.toNumber(), 0, "Expired transfers waited several seconds");
+      assert.equal(
+        stats.averageResolutionTime.toString(),
+        pool.totalResolutionTime.div(pool.totalTransfersResolved).toString()
+      );
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group W: Refund Account Override
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("W. Refund Account Override", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    let vault: PublicKey;
+
+    before(async () => {
+      // Non-ATA token account, e.g. a multisig vault
+      vault = await createAccount(connection, payerKeypair, mint, thirdParty.publicKey, Keypair.generate());
+    });
+
+    async function createWithOverride(): Promise<PublicKey> {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "vault refund", new BN(0), new BN(0), null, new BN(0), vault, false, null, null, new BN(0))
+        .accounts({
+          ...createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda),
+          refundTokenAccount: vault,
+        })
+        .signers([sender])
+        .rpc();
+      return transferPda;
+    }
+
+    it("W1. reject refunds to the override account", async () => {
+      const transferPda = await createWithOverride();
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.ok(escrow.refundTokenAccount.equals(vault));
+
+      await program.methods
+        .rejectTransfer(1)
+        .accounts({
+          ...rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda),
+          refundTokenAccount: vault,
+        })
+        .rpc();
+
+      const vaultBal = await getTokenBalance(connection, vault);
+      assert.equal(vaultBal.toString(), TRANSFER_AMOUNT.toString());
+    });
+
+    it("W2. fails when a different refund account is supplied", async () => {
+      const transferPda = await createWithOverride();
+
+      try {
+        await program.methods
+          .rejectTransfer(1)
+          .accounts({
+            ...rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda),
+            refundTokenAccount: getAta(mint, sender.publicKey),
+          })
+          .rpc();
+        assert.fail("Should fail with the wrong refund account");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidRefundAccount");
+      }
+
+      // Cleanup
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("W3. creation fails when the override is not a token account in the pool mint", async () => {
+      const otherMint = await createMint(connection, payerKeypair, payerKeypair.publicKey, null, 6);
+      const otherVault = await createAccount(connection, payerKeypair, otherMint, thirdParty.publicKey, Keypair.generate());
+
+      for (const refundAccount of [null, otherVault]) {
+        const nonce = nextNonce();
+        const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+        try {
+          await program.methods
+            .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "bad refund", new BN(0), new BN(0), null, new BN(0), otherVault, false, null, null, new BN(0))
+            .accounts({
+              ...createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda),
+              refundTokenAccount: refundAccount,
+            })
+            .signers([sender])
+            .rpc();
+          assert.fail("Invalid refund override should be rejected at creation");
+        } catch (err: any) {
+          assert.include(err.toString(), "InvalidRefundAccount");
+        }
+      }
+    });
+
+    it("W4. reject defers the refund when the override account has been closed", async () => {
+      const closingVault = await createAccount(connection, payerKeypair, mint, thirdParty.publicKey, Keypair.generate());
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "closed refund", new BN(0), new BN(0), null, new BN(0), closingVault, false, null, null, new BN(0))
+        .accounts({
+          ...createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda),
+          refundTokenAccount: closingVault,
+        })
+        .signers([sender])
+        .rpc();
+      await closeAccount(connection, payerKeypair, closingVault, thirdParty.publicKey, thirdParty);
+
+      await program.methods
+        .setDeferMissingRefunds(true)
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+      const [pendingRefundPda] = PublicKey.findProgramAddressSync([Buffer.from("refund"), transferPda.toBuffer()], programId);
+      await program.methods
+        .rejectTransfer(1)
+        .accounts({
+          ...rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda),
+          refundTokenAccount: closingVault,
+          pendingRefund: pendingRefundPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const pending = await program.account.pendingRefund.fetch(pendingRefundPda);
+      assert.equal(pending.amount.toString(), TRANSFER_AMOUNT.toString());
+      assert.isNull(await connection.getAccountInfo(transferPda));
+
+      // The deferred refund is paid to the sender's ATA
+      const balBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      await program.methods
+        .claimRefund()
+        .accounts({
+          caller: thirdParty.publicKey,
+          pool: feePoolPda,
+          mint,
+          poolTokenAccount: getAta(mint, feePoolPda),
+          senderTokenAccount: getAta(mint, sender.publicKey),
+          pendingRefund: pendingRefundPda,
+          operator,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([thirdParty])
+        .rpc();
+      const balAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(balAfter.sub(balBefore).toString(), TRANSFER_AMOUNT.toString());
+
+      await program.methods
+        .setDeferMissingRefunds(false)
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group X: Pool Config Updates
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("X. Pool Config Updates", () => {
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+
+    it("X1. operator updates several fields in one call", async () => {
+      const before = await program.account.pool.fetch(feePoolPda);
+
+      await program.methods
+        .updatePoolConfig({ ...unchanged, feeBurnBps: 1234, isPaused: true })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      let pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.feeBurnBps, 1234);
+      assert.isTrue(pool.isPaused);
+      assert.equal(pool.transferFeeBps, before.transferFeeBps);
+      assert.equal(pool.deferMissingRefunds, before.deferMissingRefunds);
+
+      // Restore
+      await program.methods
+        .updatePoolConfig({ ...unchanged, feeBurnBps: before.feeBurnBps, isPaused: false })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.feeBurnBps, before.feeBurnBps);
+      assert.isFalse(pool.isPaused);
+    });
+
+    it("X2. applies nothing when any field fails validation", async () => {
+      const before = await program.account.pool.fetch(feePoolPda);
+
+      try {
+        await program.methods
+          .updatePoolConfig({ ...unchanged, feeBurnBps: 1, isPaused: true, transferFeeBps: 10001 })
+          .accounts({ operator, pool: feePoolPda })
+          .rpc();
+        assert.fail("Should fail with an invalid transfer fee");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidTransferFee");
+      }
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.feeBurnBps, before.feeBurnBps);
+      assert.equal(pool.isPaused, before.isPaused);
+    });
+
+    it("X3. fails when non-operator updates config", async () => {
+      try {
+        await program.methods
+          .updatePoolConfig({ ...unchanged, isPaused: true })
+          .accounts({ operator: sender.publicKey, pool: feePoolPda })
+          .signers([sender])
+          .rpc();
+        assert.fail("Non-operator should not update config");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+
+    it("X4. minimal event verbosity omits the memo from TransferCreated", async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, eventVerbosity: 1 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      const sig = await program.methods
+        .createTransfer(recipient.publicKey, nonce, new BN(1_000_000), "quiet", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc({ commitment: "confirmed" });
+
+      const tx = await connection.getTransaction(sig, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+      const events = [...new anchor.EventParser(programId, program.coder).parseLogs(tx!.meta!.logMessages!)];
+      const created = events.find((e) => e.name === "transferCreated");
+      assert.equal(created!.data.memo, "");
+
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+      await program.methods
+        .updatePoolConfig({ ...unchanged, eventVerbosity: 0 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+
+    it("X5. a shorter max lifetime does not strand transfers already open", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, new BN(1_000_000), "open", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      await program.methods
+        .updatePoolConfig({ ...unchanged, maxLifetimeSeconds: new BN(1), staleFeeBps: 1000 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+
+      // Still resolvable on its original terms
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+      assert.isNull(await connection.getAccountInfo(transferPda));
+
+      await program.methods
+        .updatePoolConfig({ ...unchanged, maxLifetimeSeconds: new BN(0), staleFeeBps: 0 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group Y: Transfer Hook Mints
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("Y. Transfer Hook Mints", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    let hookMint: PublicKey;
+    let hookPoolPda: PublicKey;
+
+    function getAta2022(owner: PublicKey): PublicKey {
+      return getAssociatedTokenAddressSync(hookMint, owner, true, TOKEN_2022_PROGRAM_ID);
+    }
+
+    before(async () => {
+      // Token-2022 mint with a TransferHook pointing at an arbitrary program
+      const mintKeypair = Keypair.generate();
+      hookMint = mintKeypair.publicKey;
+      const mintLen = getMintLen([ExtensionType.TransferHook]);
+      const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: operator,
+            newAccountPubkey: hookMint,
+            space: mintLen,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          createInitializeTransferHookInstruction(hookMint, operator, Keypair.generate().publicKey, TOKEN_2022_PROGRAM_ID),
+          createInitializeMintInstruction(hookMint, 6, operator, null, TOKEN_2022_PROGRAM_ID)
+        ),
+        [mintKeypair]
+      );
+
+      const senderAta = await createAssociatedTokenAccount(
+        connection, payerKeypair, hookMint, sender.publicKey, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection, payerKeypair, hookMint, senderAta, payerKeypair, TRANSFER_AMOUNT.toNumber(), [], undefined, TOKEN_2022_PROGRAM_ID
+      );
+
+      const poolId = Keypair.generate().publicKey;
+      [hookPoolPda] = findPoolPda(programId, poolId);
+      await program.methods
+        .initPool(poolId, 0, "", "")
+        .accounts({
+          operator,
+          mint: hookMint,
+          pool: hookPoolPda,
+          poolTokenAccount: getAta2022(hookPoolPda),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    });
+
+    it("Y1. refuses transfers of a transfer-hook mint unless the pool opts in", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+
+      try {
+        await program.methods
+          .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "hooked", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts({
+            sender: sender.publicKey,
+            pool: hookPoolPda,
+            mint: hookMint,
+            poolTokenAccount: getAta2022(hookPoolPda),
+            senderTokenAccount: getAta2022(sender.publicKey),
+            transfer: transferPda,
+            recipientExpectation: findExpectationPda(programId, hookPoolPda, recipient.publicKey)[0],
+            instructionsSysvar: null,
+            couponRedemption: null,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          })
+          .signers([sender])
+          .rpc();
+        assert.fail("Transfer-hook mint should be refused");
+      } catch (err: any) {
+        assert.include(err.toString(), "TransferHookNotAllowed");
+      }
+    });
+
+    it("Y2. operator can opt the pool in to transfer hooks", async () => {
+      await program.methods
+        .updatePoolConfig({
+          transferFeeBps: null,
+          feeBurnBps: null,
+          isPaused: null,
+          deferMissingRefunds: null,
+          allowTransferHooks: true,
+          expiryBehavior: null,
+          eventVerbosity: null,
+          maxLifetimeSeconds: null,
+          staleFeeBps: null,
+          rejectUndoWindow: null,
+          couponSigner: null,
+          allowSelfTransfer: null,
+          minOperatorStake: null,
+          notifyMemo: null,
+          maxExtensionSeconds: null,
+          feeInSol: null,
+          solFeeLamports: null,
+          freeTransferCount: null,
+          feeChangeCooldown: null,
+        })
+        .accounts({ operator, pool: hookPoolPda })
+        .rpc();
+
+      const pool = await program.account.pool.fetch(hookPoolPda);
+      assert.isTrue(pool.allowTransferHooks);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group Z: Expiry Behavior
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("Z. Expiry Behavior", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+
+    it("Z1. rejects an unknown expiry behavior", async () => {
+      try {
+        await program.methods
+          .updatePoolConfig({ ...unchanged, expiryBehavior: 2 })
+          .accounts({ operator, pool: feePoolPda })
+          .rpc();
+        assert.fail("Unknown expiry behavior should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidExpiryBehavior");
+      }
+    });
+
+    it("Z2. forwards an expired transfer to the recipient minus fee", async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, expiryBehavior: 1 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      const claimableUntil = new BN(Math.floor(Date.now() / 1000) + 3);
+
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "forwarded", new BN(0), claimableUntil, null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      const fee = TRANSFER_AMOUNT.muln(pool.transferFeeBps).divn(10000);
+      const recipientBalBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+
+      // Wait for expiry (generous margin for validator clock lag)
+      await new Promise((resolve) => setTimeout(resolve, 6000));
+
+      await program.methods
+        .expireTransfer()
+        .accounts({
+          ...expireTransferAccounts(thirdParty.publicKey, sender.publicKey, feePoolPda, mint, transferPda),
+          recipientTokenAccount: getAta(mint, recipient.publicKey),
+        })
+        .signers([thirdParty])
+        .rpc();
+
+      const recipientBalAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), TRANSFER_AMOUNT.sub(fee).toString());
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, expiryBehavior: 0 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AA: Mutual Accept
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AA. Mutual Accept", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    let transferPda: PublicKey;
+
+    before(async () => {
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "mutual", new BN(0), new BN(0), null, new BN(0), null, true, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AA1. recipient alone cannot claim a mutual transfer", async () => {
+      try {
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+        assert.fail("Mutual transfer should not be claimable by the recipient alone");
+      } catch (err: any) {
+        assert.include(err.toString(), "MutualAcceptRequired");
+      }
+    });
+
+    it("AA2. fails when the operator co-signer is not the pool operator", async () => {
+      try {
+        await program.methods
+          .mutualAccept(null)
+          .accounts({
+            ...claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda),
+            operator: thirdParty.publicKey,
+          })
+          .signers([recipient, thirdParty])
+          .rpc();
+        assert.fail("Non-operator should not co-sign");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+
+    it("AA3. operator and recipient together release the funds", async () => {
+      const pool = await program.account.pool.fetch(feePoolPda);
+      const fee = TRANSFER_AMOUNT.muln(pool.transferFeeBps).divn(10000);
+      const recipientBalBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+
+      await program.methods
+        .mutualAccept(null)
+        .accounts({
+          ...claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda),
+          operator,
+        })
+        .signers([recipient])
+        .rpc();
+
+      const recipientBalAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), TRANSFER_AMOUNT.sub(fee).toString());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AB: Max Transfer Lifetime
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AB. Max Transfer Lifetime", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const STALE_FEE_BPS = 100; // 1%
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+    let transferPda: PublicKey;
+    // Created before the max lifetime was set, so it never goes stale
+    let unlimitedPda: PublicKey;
+
+    function forceResolveAccounts(transfer = transferPda) {
+      return {
+        caller: thirdParty.publicKey,
+        pool: feePoolPda,
+        mint,
+        poolTokenAccount: getAta(mint, feePoolPda),
+        senderTokenAccount: getAta(mint, sender.publicKey),
+        transfer,
+        sender: sender.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        feeVault: null,
+        senderProfile: null,
+      };
+    }
+
+    async function create(memo: string): Promise<PublicKey> {
+      const nonce = nextNonce();
+      const [pda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, memo, new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, pda))
+        .signers([sender])
+        .rpc();
+      return pda;
+    }
+
+    before(async () => {
+      unlimitedPda = await create("unlimited");
+      await program.methods
+        .updatePoolConfig({ ...unchanged, maxLifetimeSeconds: new BN(1), staleFeeBps: STALE_FEE_BPS })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+      transferPda = await create("lingering");
+    });
+
+    it("AB1. fails to force-resolve a transfer that is not stale", async () => {
+      try {
+        await program.methods
+          .forceResolve()
+          .accounts(forceResolveAccounts(unlimitedPda))
+          .signers([thirdParty])
+          .rpc();
+        assert.fail("Fresh transfer should not be force-resolved");
+      } catch (err: any) {
+        assert.include(err.toString(), "TransferNotStale");
+      }
+    });
+
+    it("AB2. a stale transfer can no longer be claimed", async () => {
+      const transfer = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(transfer.staleAt.toNumber(), transfer.createdAt.toNumber() + 1);
+      assert.equal(transfer.staleFeeBps, STALE_FEE_BPS);
+
+      // Wait past the max lifetime (generous margin for validator clock lag)
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      try {
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+        assert.fail("Stale transfer should not be claimable");
+      } catch (err: any) {
+        assert.include(err.toString(), "TransferStale");
+      }
+    });
+
+    it("AB3. anyone can force-resolve a stale transfer, refunding minus the stale fee", async () => {
+      const BURN_BPS = 4000;
+      await program.methods
+        .setFeeBurnBps(BURN_BPS)
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      const feesBefore = (await program.account.pool.fetch(feePoolPda)).collectedFees;
+      const supplyBefore = (await getMint(connection, mint)).supply;
+
+      await program.methods
+        .forceResolve()
+        .accounts(forceResolveAccounts())
+        .signers([thirdParty])
+        .rpc();
+
+      const staleFee = TRANSFER_AMOUNT.muln(STALE_FEE_BPS).divn(10000);
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(senderBalAfter.sub(senderBalBefore).toString(), TRANSFER_AMOUNT.sub(staleFee).toString());
+
+      // The stale fee is split by fee_burn_bps like any other fee
+      const staleFeeBurned = staleFee.muln(BURN_BPS).divn(10000);
+      const supplyAfter = (await getMint(connection, mint)).supply;
+      assert.equal((supplyBefore - supplyAfter).toString(), staleFeeBurned.toString());
+      const pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.collectedFees.sub(feesBefore).toString(), staleFee.sub(staleFeeBurned).toString());
+
+      const info = await connection.getAccountInfo(transferPda);
+      assert.isNull(info);
+
+      await program.methods
+        .setFeeBurnBps(0)
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+
+    it("AB4. the lifetime and stale fee are fixed at creation, not read from the pool later", async () => {
+      // Shortening the pool's lifetime does not make an older transfer stale
+      const unlimited = await program.account.secureTransfer.fetch(unlimitedPda);
+      assert.equal(unlimited.staleAt.toNumber(), 0);
+      assert.equal(unlimited.staleFeeBps, 0);
+
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, unlimitedPda))
+        .signers([recipient])
+        .rpc();
+      assert.isNull(await connection.getAccountInfo(unlimitedPda));
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, maxLifetimeSeconds: new BN(0), staleFeeBps: 0 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AC: Reversible Rejects
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AC. Reversible Rejects", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const UNDO_WINDOW = 3;
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+    let transferPda: PublicKey;
+
+    function finalizeRejectAccounts() {
+      return {
+        caller: thirdParty.publicKey,
+        pool: feePoolPda,
+        mint,
+        poolTokenAccount: getAta(mint, feePoolPda),
+        senderTokenAccount: getAta(mint, sender.publicKey),
+        refundTokenAccount: null,
+        transfer: transferPda,
+        sender: sender.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        senderProfile: null,
+      };
+    }
+
+    before(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, rejectUndoWindow: new BN(UNDO_WINDOW) })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "oops", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AC1. reject holds the transfer as pending instead of refunding", async () => {
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+
+      await program.methods
+        .rejectTransfer(1)
+        .accounts(rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda))
+        .rpc();
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.deepEqual(escrow.status, { rejectedPending: {} });
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(senderBalAfter.toString(), senderBalBefore.toString());
+    });
+
+    it("AC2. fails to finalize while the undo window is open", async () => {
+      try {
+        await program.methods
+          .finalizeReject()
+          .accounts(finalizeRejectAccounts())
+          .signers([thirdParty])
+          .rpc();
+        assert.fail("Finalize should wait for the undo window");
+      } catch (err: any) {
+        assert.include(err.toString(), "RejectUndoWindowOpen");
+      }
+    });
+
+    it("AC3. operator can undo the reject within the window", async () => {
+      await program.methods
+        .undoReject()
+        .accounts({ operator, pool: feePoolPda, transfer: transferPda })
+        .rpc();
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.deepEqual(escrow.status, { active: {} });
+    });
+
+    it("AC4. after the window the reject can only be finalized", async () => {
+      await program.methods
+        .rejectTransfer(2)
+        .accounts(rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda))
+        .rpc();
+
+      // Wait past the undo window (generous margin for validator clock lag)
+      await new Promise((resolve) => setTimeout(resolve, (UNDO_WINDOW + 3) * 1000));
+
+      try {
+        await program.methods
+          .undoReject()
+          .accounts({ operator, pool: feePoolPda, transfer: transferPda })
+          .rpc();
+        assert.fail("Undo should fail after the window");
+      } catch (err: any) {
+        assert.include(err.toString(), "RejectUndoWindowClosed");
+      }
+
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      await program.methods
+        .finalizeReject()
+        .accounts(finalizeRejectAccounts())
+        .signers([thirdParty])
+        .rpc();
+
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(senderBalAfter.sub(senderBalBefore).toString(), TRANSFER_AMOUNT.toString());
+      assert.isNull(await connection.getAccountInfo(transferPda));
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, rejectUndoWindow: new BN(0) })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AD: Fee Coupons
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AD. Fee Coupons", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    const DISCOUNT_BPS = 5000; // half off the pool fee
+    const couponSigner = Keypair.generate();
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+
+    function couponMessage(senderKey: PublicKey, discountBps: number, expiry: BN): Buffer {
+      const discount = Buffer.alloc(2);
+      discount.writeUInt16LE(discountBps);
+      return Buffer.concat([
+        Buffer.from("handshake:fee_coupon"),
+        feePoolPda.toBuffer(),
+        senderKey.toBuffer(),
+        discount,
+        expiry.toArrayLike(Buffer, "le", 8),
+      ]);
+    }
+
+    function couponRedemptionPda(senderKey: PublicKey): PublicKey {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("coupon"), feePoolPda.toBuffer(), senderKey.toBuffer()],
+        programId
+      )[0];
+    }
+
+    async function sendWithCoupon(transferPda: PublicKey, nonce: BN, expiry: BN) {
+      const verifyIx = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: couponSigner.secretKey,
+        message: couponMessage(sender.publicKey, DISCOUNT_BPS, expiry),
+      });
+      const createIx = await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "coupon", new BN(0), new BN(0), null, new BN(0), null, false, {
+          discountBps: DISCOUNT_BPS,
+          expiry,
+        }, null, new BN(0))
+        .accounts({
+          ...createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda),
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+          couponRedemption: couponRedemptionPda(sender.publicKey),
+        })
+        .instruction();
+      await sendAndConfirmTransaction(connection, new Transaction().add(verifyIx, createIx), [sender]);
+    }
+
+    before(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, couponSigner: couponSigner.publicKey })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+
+    it("AD1. a coupon halves the fee charged at claim", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await sendWithCoupon(transferPda, nonce, new BN(Math.floor(Date.now() / 1000) + 600));
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.feeDiscountBps, DISCOUNT_BPS);
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      const fullFee = TRANSFER_AMOUNT.muln(pool.transferFeeBps).divn(10000);
+      const fee = fullFee.sub(fullFee.muln(DISCOUNT_BPS).divn(10000));
+      const recipientBalBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+
+      const recipientBalAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), TRANSFER_AMOUNT.sub(fee).toString());
+    });
+
+    it("AD2. fails to redeem a second coupon for the same sender", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      try {
+        await sendWithCoupon(transferPda, nonce, new BN(Math.floor(Date.now() / 1000) + 600));
+        assert.fail("Coupon should only be redeemable once per sender");
+      } catch (err: any) {
+        assert.include(err.toString() + (err.logs ?? []).join("\n"), "already in use");
+      }
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, couponSigner: PublicKey.default })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AE: Operator Fee Account
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AE. Operator Fee Account", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    let feeAccount: PublicKey;
+
+    function withdrawFeesAccounts(destination: PublicKey) {
+      return {
+        operator,
+        pool: feePoolPda,
+        mint,
+        poolTokenAccount: getAta(mint, feePoolPda),
+        operatorTokenAccount: destination,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        feeVault: null,
+      };
+    }
+
+    before(async () => {
+      feeAccount = getAta(mint, thirdParty.publicKey);
+
+      // Accrue some fees to withdraw
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "fees", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+    });
+
+    it("AE1. operator registers a fee account", async () => {
+      await program.methods
+        .setOperatorFeeAccount()
+        .accounts({ operator, pool: feePoolPda, feeAccount, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.operatorFeeAccount.toBase58(), feeAccount.toBase58());
+    });
+
+    it("AE2. fails to withdraw fees anywhere but the registered account", async () => {
+      try {
+        await program.methods
+          .withdrawFees()
+          .accounts(withdrawFeesAccounts(getAta(mint, operator)))
+          .rpc();
+        assert.fail("Withdrawal should go to the registered fee account");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidFeeAccount");
+      }
+    });
+
+    it("AE3. withdraws fees to the registered account", async () => {
+      const fees = (await program.account.pool.fetch(feePoolPda)).collectedFees;
+      const balBefore = await getTokenBalance(connection, feeAccount);
+
+      await program.methods
+        .withdrawFees()
+        .accounts(withdrawFeesAccounts(feeAccount))
+        .rpc();
+
+      const balAfter = await getTokenBalance(connection, feeAccount);
+      assert.equal(balAfter.sub(balBefore).toString(), fees.toString());
+    });
+
+    after(async () => {
+      await program.methods
+        .setOperatorFeeAccount()
+        .accounts({ operator, pool: feePoolPda, feeAccount: null, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AF: Self Transfers
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AF. Self Transfers", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+
+    async function createSelfTransfer(): Promise<PublicKey> {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, sender.publicKey, nonce);
+      await program.methods
+        .createTransfer(sender.publicKey, nonce, TRANSFER_AMOUNT, "to self", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+      return transferPda;
+    }
+
+    it("AF1. rejects a transfer to the sender by default", async () => {
+      try {
+        await createSelfTransfer();
+        assert.fail("Self transfer should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "SelfTransfer");
+      }
+    });
+
+    it("AF2. allows a transfer to the sender when the pool opts in", async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, allowSelfTransfer: true })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      const transferPda = await createSelfTransfer();
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.recipient.toBase58(), sender.publicKey.toBase58());
+
+      // Cleanup
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, allowSelfTransfer: false })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AG: Accounting Periods
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AG. Accounting Periods", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+
+    it("AG1. deposits accrue to both period and lifetime counters", async () => {
+      const before = await program.account.pool.fetch(feePoolPda);
+
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "period", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      const after = await program.account.pool.fetch(feePoolPda);
+      assert.equal(after.periodDeposits.sub(before.periodDeposits).toString(), TRANSFER_AMOUNT.toString());
+      assert.equal(after.periodWithdrawals.sub(before.periodWithdrawals).toString(), TRANSFER_AMOUNT.toString());
+      assert.equal(after.totalDeposits.sub(before.totalDeposits).toString(), TRANSFER_AMOUNT.toString());
+    });
+
+    it("AG2. snapshot zeroes period counters and keeps lifetime totals", async () => {
+      const before = await program.account.pool.fetch(feePoolPda);
+
+      await program.methods
+        .snapshotPeriod()
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      const after = await program.account.pool.fetch(feePoolPda);
+      assert.equal(after.periodDeposits.toNumber(), 0);
+      assert.equal(after.periodWithdrawals.toNumber(), 0);
+      assert.equal(after.periodFeesCollected.toNumber(), 0);
+      assert.isAtLeast(after.periodStartedAt.toNumber(), before.periodStartedAt.toNumber());
+      assert.equal(after.totalDeposits.toString(), before.totalDeposits.toString());
+      assert.equal(after.totalWithdrawals.toString(), before.totalWithdrawals.toString());
+    });
+
+    it("AG3. fails when non-operator snapshots", async () => {
+      try {
+        await program.methods
+          .snapshotPeriod()
+          .accounts({ operator: sender.publicKey, pool: feePoolPda })
+          .signers([sender])
+          .rpc();
+        assert.fail("Non-operator should not snapshot");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AH: Operator Stake
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AH. Operator Stake", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const MIN_STAKE = new BN(5_000_000);
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+    let stakeVault: PublicKey;
+    let transferPda: PublicKey;
+
+    function stakeAccounts() {
+      return {
+        operator,
+        pool: feePoolPda,
+        mint,
+        operatorTokenAccount: getAta(mint, operator),
+        stakeVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      };
+    }
+
+    before(async () => {
+      [stakeVault] = PublicKey.findProgramAddressSync([Buffer.from("stake"), feePoolPda.toBuffer()], programId);
+      await mintTo(connection, payerKeypair, mint, getAta(mint, operator), payerKeypair, MIN_STAKE.toNumber());
+
+      await program.methods
+        .updatePoolConfig({ ...unchanged, minOperatorStake: MIN_STAKE })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "staked", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AH1. an unstaked operator cannot reject", async () => {
+      try {
+        await program.methods
+          .rejectTransfer(1)
+          .accounts(rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda))
+          .rpc();
+        assert.fail("Unstaked operator should not reject");
+      } catch (err: any) {
+        assert.include(err.toString(), "InsufficientOperatorStake");
+      }
+    });
+
+    it("AH2. a staked operator can reject", async () => {
+      await program.methods
+        .initStakeVault()
+        .accounts({
+          operator,
+          pool: feePoolPda,
+          mint,
+          stakeVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      // The vault bump is cached so deposits and withdrawals skip find_program_address
+      const [, stakeVaultBump] = PublicKey.findProgramAddressSync([Buffer.from("stake"), feePoolPda.toBuffer()], programId);
+      assert.equal((await program.account.pool.fetch(feePoolPda)).stakeVaultBump, stakeVaultBump);
+
+      const sig = await program.methods
+        .depositOperatorStake(MIN_STAKE)
+        .accounts(stakeAccounts())
+        .rpc({ commitment: "confirmed" });
+      assert.equal((await getTokenBalance(connection, stakeVault)).toString(), MIN_STAKE.toString());
+
+      // A deposit is one transfer_checked CPI plus account checks
+      assert.isBelow(await getComputeUnits(connection, sig), 30_000);
+
+      // The minimum can't be lowered while the transfer it backs is open
+      try {
+        await program.methods
+          .updatePoolConfig({ ...unchanged, minOperatorStake: new BN(0) })
+          .accounts({ operator, pool: feePoolPda })
+          .rpc();
+        assert.fail("Minimum stake should not drop with transfers outstanding");
+      } catch (err: any) {
+        assert.include(err.toString(), "OutstandingTransfers");
+      }
+
+      await program.methods
+        .rejectTransfer(1)
+        .accounts(rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda))
+        .rpc();
+    });
+
+    it("AH3. operator withdraws the stake once the minimum is lifted", async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, minOperatorStake: new BN(0) })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      await program.methods
+        .withdrawOperatorStake(MIN_STAKE)
+        .accounts(stakeAccounts())
+        .rpc();
+
+      const pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.operatorStake.toNumber(), 0);
+      assert.equal((await getTokenBalance(connection, stakeVault)).toNumber(), 0);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AI: Fee Overrides
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AI. Fee Overrides", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    const OVERRIDE_BPS = 100; // 1%
+    let transferPda: PublicKey;
+
+    before(async () => {
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "negotiated", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AI1. fails to set an override above the fee ceiling", async () => {
+      try {
+        await program.methods
+          .setFeeOverride(1001)
+          .accounts({ operator, sender: sender.publicKey, pool: feePoolPda, transfer: transferPda })
+          .signers([sender])
+          .rpc();
+        assert.fail("Override above the ceiling should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidFeeConfig");
+      }
+    });
+
+    it("AI2. the override replaces the pool fee at claim", async () => {
+      await program.methods
+        .setFeeOverride(OVERRIDE_BPS)
+        .accounts({ operator, sender: sender.publicKey, pool: feePoolPda, transfer: transferPda })
+        .signers([sender])
+        .rpc();
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.feeOverrideBps, OVERRIDE_BPS);
+
+      const fee = TRANSFER_AMOUNT.muln(OVERRIDE_BPS).divn(10000);
+      const recipientBalBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+
+      const recipientBalAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), TRANSFER_AMOUNT.sub(fee).toString());
+    });
+
+    it("AI3. TransferRejected reports the overridden rate", async () => {
+      const nonce = nextNonce();
+      const [overriddenPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "negotiated", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, overriddenPda))
+        .signers([sender])
+        .rpc();
+      await program.methods
+        .setFeeOverride(OVERRIDE_BPS)
+        .accounts({ operator, sender: sender.publicKey, pool: feePoolPda, transfer: overriddenPda })
+        .signers([sender])
+        .rpc();
+
+      const sig = await program.methods
+        .rejectTransfer(1)
+        .accounts(rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, overriddenPda))
+        .rpc({ commitment: "confirmed" });
+
+      const tx = await connection.getTransaction(sig, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+      const events = [...new anchor.EventParser(programId, program.coder).parseLogs(tx!.meta!.logMessages!)];
+      const rejected = events.find((e) => e.name === "transferRejected");
+      assert.equal(rejected!.data.transferFeeBps, OVERRIDE_BPS);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AJ: Milestones
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AJ. Milestones", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    let transferPda: PublicKey;
+
+    function releaseAccounts() {
+      return {
+        operator,
+        pool: feePoolPda,
+        mint,
+        poolTokenAccount: getAta(mint, feePoolPda),
+        recipientTokenAccount: getAta(mint, recipient.publicKey),
+        transfer: transferPda,
+        sender: sender.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        feeVault: null,
+      };
+    }
+
+    before(async () => {
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "staged", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AJ1. fails to set milestones that do not cover the amount", async () => {
+      try {
+        await program.methods
+          .setMilestones([5000, 4000])
+          .accounts({ sender: sender.publicKey, pool: feePoolPda, transfer: transferPda })
+          .signers([sender])
+          .rpc();
+        assert.fail("Milestones summing below 10000 should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidMilestones");
+      }
+    });
+
+    it("AJ2. a staged transfer cannot be claimed outright", async () => {
+      await program.methods
+        .setMilestones([5000, 5000])
+        .accounts({ sender: sender.publicKey, pool: feePoolPda, transfer: transferPda })
+        .signers([sender])
+        .rpc();
+
+      try {
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+        assert.fail("Staged transfer should not be claimable");
+      } catch (err: any) {
+        assert.include(err.toString(), "MilestonesPending");
+      }
+    });
+
+    it("AJ3. releases each milestone with a proportional fee, then closes", async () => {
+      const half = TRANSFER_AMOUNT.divn(2);
+      const fee = half.muln(FEE_BPS).divn(10000);
+      const recipientAta = getAta(mint, recipient.publicKey);
+
+      let recipientBalBefore = await getTokenBalance(connection, recipientAta);
+      await program.methods.releaseMilestone(0).accounts(releaseAccounts()).rpc();
+      let recipientBalAfter = await getTokenBalance(connection, recipientAta);
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), half.sub(fee).toString());
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.amount.toString(), half.toString());
+      assert.isTrue(escrow.milestones[0].released);
+      assert.isFalse(escrow.milestones[1].released);
+
+      try {
+        await program.methods.releaseMilestone(0).accounts(releaseAccounts()).rpc();
+        assert.fail("Milestone should only release once");
+      } catch (err: any) {
+        assert.include(err.toString(), "MilestoneAlreadyReleased");
+      }
+
+      recipientBalBefore = recipientBalAfter;
+      await program.methods.releaseMilestone(1).accounts(releaseAccounts()).rpc();
+      recipientBalAfter = await getTokenBalance(connection, recipientAta);
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), half.sub(fee).toString());
+
+      assert.isNull(await connection.getAccountInfo(transferPda));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AK: Token Account Ownership
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AK. Token Account Ownership", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    let transferPda: PublicKey;
+
+    before(async () => {
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "owners", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AK1. reject fails when the sender token account belongs to someone else", async () => {
+      try {
+        await program.methods
+          .rejectTransfer(null)
+          .accounts({
+            ...rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda),
+            senderTokenAccount: getAta(mint, thirdParty.publicKey),
+          })
+          .rpc();
+        assert.fail("Foreign sender token account should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "ConstraintTokenOwner");
+      }
+    });
+
+    it("AK2. reject fails when the pool token account belongs to someone else", async () => {
+      try {
+        await program.methods
+          .rejectTransfer(null)
+          .accounts({
+            ...rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, transferPda),
+            poolTokenAccount: getAta(mint, thirdParty.publicKey),
+          })
+          .rpc();
+        assert.fail("Foreign pool token account should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "ConstraintTokenOwner");
+      }
+    });
+
+    it("AK3. claim fails when the recipient token account belongs to someone else", async () => {
+      try {
+        await program.methods
+          .claimTransfer(null)
+          .accounts({
+            ...claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda),
+            recipientTokenAccount: getAta(mint, thirdParty.publicKey),
+          })
+          .signers([recipient])
+          .rpc();
+        assert.fail("Foreign recipient token account should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "ConstraintTokenOwner");
+      }
+    });
+
+    it("AK4. the transfer is untouched after the failed attempts", async () => {
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.deepEqual(escrow.status, { active: {} });
+      assert.equal(escrow.amount.toString(), TRANSFER_AMOUNT.toString());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AL: Fee Vault
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AL. Fee Vault", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    const FEE = TRANSFER_AMOUNT.muln(FEE_BPS).divn(10000);
+    let vaultPoolPda: PublicKey;
+    let feeVault: PublicKey;
+    let transferPda: PublicKey;
+
+    before(async () => {
+      const vaultPoolId = Keypair.generate().publicKey;
+      [vaultPoolPda] = findPoolPda(programId, vaultPoolId);
+      [feeVault] = PublicKey.findProgramAddressSync([Buffer.from("fee_vault"), vaultPoolPda.toBuffer()], programId);
+
+      await program.methods
+        .initPool(vaultPoolId, FEE_BPS, "", "")
+        .accounts({
+          operator,
+          mint,
+          pool: vaultPoolPda,
+          poolTokenAccount: getAta(mint, vaultPoolPda),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "vaulted", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, vaultPoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AL1. operator initializes the fee vault", async () => {
+      await program.methods
+        .initFeeVault()
+        .accounts({
+          operator,
+          pool: vaultPoolPda,
+          mint,
+          poolTokenAccount: getAta(mint, vaultPoolPda),
+          feeVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const pool = await program.account.pool.fetch(vaultPoolPda);
+      assert.equal(pool.feeVault.toBase58(), feeVault.toBase58());
+    });
+
+    it("AL2. fails to claim without the fee vault", async () => {
+      try {
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, vaultPoolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+        assert.fail("Claim should require the fee vault");
+      } catch (err: any) {
+        assert.include(err.toString(), "FeeVaultRequired");
+      }
+    });
+
+    it("AL3. claim routes the fee into the vault", async () => {
+      await program.methods
+        .claimTransfer(null)
+        .accounts({
+          ...claimTransferAccounts(recipient.publicKey, sender.publicKey, vaultPoolPda, mint, transferPda),
+          feeVault,
+        })
+        .signers([recipient])
+        .rpc();
+
+      assert.equal((await getTokenBalance(connection, feeVault)).toString(), FEE.toString());
+      assert.equal((await getTokenBalance(connection, getAta(mint, vaultPoolPda))).toNumber(), 0);
+    });
+
+    it("AL4. withdraw_fees pulls from the vault", async () => {
+      const operatorBalBefore = await getTokenBalance(connection, getAta(mint, operator));
+
+      await program.methods
+        .withdrawFees()
+        .accounts({
+          operator,
+          pool: vaultPoolPda,
+          mint,
+          poolTokenAccount: getAta(mint, vaultPoolPda),
+          operatorTokenAccount: getAta(mint, operator),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          feeVault,
+        })
+        .rpc();
+
+      const operatorBalAfter = await getTokenBalance(connection, getAta(mint, operator));
+      assert.equal(operatorBalAfter.sub(operatorBalBefore).toString(), FEE.toString());
+      assert.equal((await getTokenBalance(connection, feeVault)).toNumber(), 0);
+    });
+
+    it("AL5. reset_pool requires the fee vault", async () => {
+      try {
+        await program.methods
+          .resetPool()
+          .accounts({ operator, pool: vaultPoolPda, feeVault: null })
+          .rpc();
+        assert.fail("Reset should require the fee vault");
+      } catch (err: any) {
+        assert.include(err.toString(), "FeeVaultRequired");
+      }
+
+      await program.methods
+        .resetPool()
+        .accounts({ operator, pool: vaultPoolPda, feeVault })
+        .rpc();
+      assert.equal((await program.account.pool.fetch(vaultPoolPda)).collectedFees.toNumber(), 0);
+    });
+
+    it("AL6. close_pool closes the fee vault", async () => {
+      await program.methods
+        .closePool(new BN(0))
+        .accounts({
+          operator,
+          pool: vaultPoolPda,
+          mint,
+          poolTokenAccount: getAta(mint, vaultPoolPda),
+          operatorTokenAccount: getAta(mint, operator),
+          feeVault,
+          stakeVault: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.isNull(await connection.getAccountInfo(feeVault));
+      assert.isNull(await connection.getAccountInfo(vaultPoolPda));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AM: Payout Memos
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AM. Payout Memos", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+    const MEMO = "invoice-42";
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+    let transferPda: PublicKey;
+
+    before(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, notifyMemo: true })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, MEMO, new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, notifyMemo: false })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+
+    it("AM1. fails to claim without the memo program", async () => {
+      try {
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+        assert.fail("Claim should require the memo program");
+      } catch (err: any) {
+        assert.include(err.toString(), "MemoProgramRequired");
+      }
+    });
+
+    it("AM2. claim attaches the transfer memo to the payout", async () => {
+      const sig = await program.methods
+        .claimTransfer(null)
+        .accounts({
+          ...claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda),
+          memoProgram: MEMO_PROGRAM_ID,
+        })
+        .signers([recipient])
+        .rpc({ commitment: "confirmed" });
+
+      const tx = await connection.getTransaction(sig, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+      const logs = tx!.meta!.logMessages!;
+      assert.isTrue(logs.some((log) => log.includes(MEMO_PROGRAM_ID.toBase58())), "Memo program should be invoked");
+      assert.isTrue(logs.some((log) => log.includes(MEMO)), "Memo should be logged");
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AN: Reject All From Sender
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AN. Reject All From Sender", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const transferPdas: PublicKey[] = [];
+
+    function rejectAllAccounts(senderKey: PublicKey) {
+      return {
+        operator,
+        pool: feePoolPda,
+        mint,
+        poolTokenAccount: getAta(mint, feePoolPda),
+        senderTokenAccount: getAta(mint, senderKey),
+        sender: senderKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        senderProfile: null,
+      };
+    }
+
+    function transferRemainingAccounts() {
+      return transferPdas.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }));
+    }
+
+    before(async () => {
+      for (let i = 0; i < 2; i++) {
+        const nonce = nextNonce();
+        const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+        await program.methods
+          .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "offboard", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+          .signers([sender])
+          .rpc();
+        transferPdas.push(transferPda);
+      }
+    });
+
+    it("AN1. fails when a transfer belongs to a different sender", async () => {
+      try {
+        await program.methods
+          .rejectAllFromSender(1)
+          .accounts(rejectAllAccounts(thirdParty.publicKey))
+          .remainingAccounts(transferRemainingAccounts())
+          .rpc();
+        assert.fail("Transfers from another sender should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+
+    it("AN2. rejects and refunds every transfer from the sender", async () => {
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+
+      await program.methods
+        .rejectAllFromSender(1)
+        .accounts(rejectAllAccounts(sender.publicKey))
+        .remainingAccounts(transferRemainingAccounts())
+        .rpc();
+
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(senderBalAfter.sub(senderBalBefore).toString(), TRANSFER_AMOUNT.muln(2).toString());
+      for (const transferPda of transferPdas) {
+        assert.isNull(await connection.getAccountInfo(transferPda));
+      }
+    });
+
+    it("AN3. a full batch fits the default compute budget", async () => {
+      // One transfer rejected alone, then MAX_BATCH_SIZE more rejected together
+      transferPdas.length = 0;
+      for (let i = 0; i <= MAX_BATCH_SIZE; i++) {
+        const nonce = nextNonce();
+        const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+        await program.methods
+          .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "offboard", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+          .signers([sender])
+          .rpc();
+        transferPdas.push(transferPda);
+      }
+
+      const rejectAll = async (batch: PublicKey[]) =>
+        getComputeUnits(
+          connection,
+          await program.methods
+            .rejectAllFromSender(1)
+            .accounts(rejectAllAccounts(sender.publicKey))
+            .remainingAccounts(batch.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+            .rpc({ commitment: "confirmed" })
+        );
+      const single = await rejectAll(transferPdas.slice(0, 1));
+      const full = await rejectAll(transferPdas.slice(1));
+
+      const perItem = (full - single) / (MAX_BATCH_SIZE - 1);
+      assert.isBelow(full, DEFAULT_COMPUTE_UNITS);
+      assert.isBelow(perItem, DEFAULT_COMPUTE_UNITS / MAX_BATCH_SIZE);
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AO: Exact Amounts
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AO. Exact Amounts", () => {
+    const TRANSFER_AMOUNT = new BN(10 * 1_000_000);
+    const EXACT_AMOUNT = new BN(9 * 1_000_000);
+    const FEE = EXACT_AMOUNT.muln(FEE_BPS).divn(10000);
+    const CHANGE = TRANSFER_AMOUNT.sub(EXACT_AMOUNT).sub(FEE);
+    let transferPda: PublicKey;
+
+    it("AO1. fails when the escrow cannot cover the exact amount plus fee", async () => {
+      const nonce = nextNonce();
+      const [underfundedPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      try {
+        await program.methods
+          .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "underpaid", new BN(0), new BN(0), null, new BN(0), null, false, null, TRANSFER_AMOUNT, new BN(0))
+          .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, underfundedPda))
+          .signers([sender])
+          .rpc();
+        assert.fail("Underfunded exact amount should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "ExactAmountUnderfunded");
+      }
+    });
+
+    it("AO2. claim requires the sender token account when change is owed", async () => {
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "overpaid", new BN(0), new BN(0), null, new BN(0), null, false, null, EXACT_AMOUNT, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      // The fee rate is locked at creation so the escrow check holds at claim
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.lockedFeeBps, FEE_BPS);
+
+      try {
+        await program.methods
+          .claimTransfer(null)
+          .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+          .signers([recipient])
+          .rpc();
+        assert.fail("Claim should require the sender token account");
+      } catch (err: any) {
+        assert.include(err.toString(), "SenderTokenAccountRequired");
+      }
+    });
+
+    it("AO3. claim pays the exact amount and returns the change", async () => {
+      const recipientBalBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      const senderBalBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+
+      await program.methods
+        .claimTransfer(null)
+        .accounts({
+          ...claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda),
+          senderTokenAccount: getAta(mint, sender.publicKey),
+        })
+        .signers([recipient])
+        .rpc();
+
+      const recipientBalAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      const senderBalAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      assert.equal(recipientBalAfter.sub(recipientBalBefore).toString(), EXACT_AMOUNT.toString());
+      assert.equal(senderBalAfter.sub(senderBalBefore).toString(), CHANGE.toString());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AP: Deadline Extensions
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AP. Deadline Extensions", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const MAX_EXTENSION = new BN(3600);
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+    let transferPda: PublicKey;
+    let claimableUntil: BN;
+
+    function extendAccounts() {
+      return { sender: sender.publicKey, recipient: recipient.publicKey, pool: feePoolPda, transfer: transferPda };
+    }
+
+    before(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, maxExtensionSeconds: MAX_EXTENSION })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+
+      claimableUntil = new BN(Math.floor(Date.now() / 1000) + 600);
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "extend me", new BN(0), claimableUntil, null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, maxExtensionSeconds: new BN(0) })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+
+    it("AP1. both parties extend the deadline", async () => {
+      await program.methods
+        .extendTransfer(new BN(1800))
+        .accounts(extendAccounts())
+        .signers([sender, recipient])
+        .rpc();
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.claimableUntil.toString(), claimableUntil.addn(1800).toString());
+      assert.equal(escrow.extendedBy.toNumber(), 1800);
+    });
+
+    it("AP2. fails to extend past the pool cap", async () => {
+      try {
+        await program.methods
+          .extendTransfer(new BN(1801))
+          .accounts(extendAccounts())
+          .signers([sender, recipient])
+          .rpc();
+        assert.fail("Extension past the cap should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "ExtensionCapExceeded");
+      }
+    });
+
+    it("AP3. fails without the recipient's signature", async () => {
+      try {
+        await program.methods
+          .extendTransfer(new BN(60))
+          .accounts({ ...extendAccounts(), recipient: thirdParty.publicKey })
+          .signers([sender, thirdParty])
+          .rpc();
+        assert.fail("Extension should require the recipient");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AQ: Net Received Quotes
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AQ. Net Received Quotes", () => {
+    const AMOUNT = new BN(1_000_000);
+    const MINT_FEE_BPS = 100; // 1%
+    let feeMint: PublicKey;
+    let feeMintPoolPda: PublicKey;
+
+    before(async () => {
+      // Token-2022 mint with a transfer-fee extension
+      const mintKeypair = Keypair.generate();
+      feeMint = mintKeypair.publicKey;
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: operator,
+            newAccountPubkey: feeMint,
+            space: mintLen,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          createInitializeTransferFeeConfigInstruction(feeMint, operator, operator, MINT_FEE_BPS, BigInt(1_000_000_000), TOKEN_2022_PROGRAM_ID),
+          createInitializeMintInstruction(feeMint, 6, operator, null, TOKEN_2022_PROGRAM_ID)
+        ),
+        [mintKeypair]
+      );
+
+      const poolId = Keypair.generate().publicKey;
+      [feeMintPoolPda] = findPoolPda(programId, poolId);
+      await program.methods
+        .initPool(poolId, FEE_BPS, "", "")
+        .accounts({
+          operator,
+          mint: feeMint,
+          pool: feeMintPoolPda,
+          poolTokenAccount: getAssociatedTokenAddressSync(feeMint, feeMintPoolPda, true, TOKEN_2022_PROGRAM_ID),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    });
+
+    it("AQ1. quotes only the pool fee for a plain mint", async () => {
+      const quote = await program.methods
+        .quoteNetReceived(AMOUNT)
+        .accounts({ pool: feePoolPda, mint })
+        .view();
+
+      const poolFee = AMOUNT.muln(FEE_BPS).divn(10000);
+      assert.equal(quote.poolFee.toString(), poolFee.toString());
+      assert.equal(quote.mintFee.toNumber(), 0);
+      assert.equal(quote.netReceived.toString(), AMOUNT.sub(poolFee).toString());
+      assert.equal(quote.solFeeLamports.toNumber(), 0);
+    });
+
+    it("AQ2. deducts the mint transfer fee from the payout", async () => {
+      const quote = await program.methods
+        .quoteNetReceived(AMOUNT)
+        .accounts({ pool: feeMintPoolPda, mint: feeMint })
+        .view();
+
+      const netAmount = AMOUNT.sub(AMOUNT.muln(FEE_BPS).divn(10000));
+      // Token-2022 rounds the withheld fee up
+      const mintFee = netAmount.muln(MINT_FEE_BPS).addn(9999).divn(10000);
+      assert.equal(quote.netAmount.toString(), netAmount.toString());
+      assert.equal(quote.mintFee.toString(), mintFee.toString());
+      assert.equal(quote.netReceived.toString(), netAmount.sub(mintFee).toString());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AR: Recipient Updates
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AR. Recipient Updates", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    let transferPda: PublicKey;
+
+    function updateRecipientAccounts() {
+      return { recipient: recipient.publicKey, operator, pool: feePoolPda, transfer: transferPda };
+    }
+
+    before(async () => {
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "lost wallet", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AR1. fails to redirect the transfer to the operator", async () => {
+      try {
+        await program.methods
+          .updateRecipient(operator)
+          .accounts(updateRecipientAccounts())
+          .signers([recipient])
+          .rpc();
+        assert.fail("Redirecting to the operator should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidRecipient");
+      }
+    });
+
+    it("AR2. recipient and operator redirect the transfer, and the new recipient claims", async () => {
+      await program.methods
+        .updateRecipient(thirdParty.publicKey)
+        .accounts(updateRecipientAccounts())
+        .signers([recipient])
+        .rpc();
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(escrow.recipient.toBase58(), thirdParty.publicKey.toBase58());
+
+      const fee = TRANSFER_AMOUNT.muln(FEE_BPS).divn(10000);
+      const balBefore = await getTokenBalance(connection, getAta(mint, thirdParty.publicKey));
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(thirdParty.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([thirdParty])
+        .rpc();
+      const balAfter = await getTokenBalance(connection, getAta(mint, thirdParty.publicKey));
+      assert.equal(balAfter.sub(balBefore).toString(), TRANSFER_AMOUNT.sub(fee).toString());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AS: Accounting Reconciliation
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AS. Accounting Reconciliation", () => {
+    const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+    let programData: PublicKey;
+    let proposalPda: PublicKey;
+
+    function proposeAccounts(admin: PublicKey) {
+      return {
+        admin,
+        program: programId,
+        programData,
+        pool: feePoolPda,
+        proposal: proposalPda,
+        systemProgram: SystemProgram.programId,
+      };
+    }
+
+    before(async () => {
+      [programData] = PublicKey.findProgramAddressSync([programId.toBuffer()], BPF_LOADER_UPGRADEABLE_ID);
+      [proposalPda] = PublicKey.findProgramAddressSync([Buffer.from("reconcile"), feePoolPda.toBuffer()], programId);
+    });
+
+    it("AS1. fails to propose without the upgrade authority", async () => {
+      try {
+        await program.methods
+          .proposeReconciliation(new BN(0))
+          .accounts(proposeAccounts(thirdParty.publicKey))
+          .signers([thirdParty])
+          .rpc();
+        assert.fail("Only the upgrade authority should propose");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+
+    it("AS2. the correction cannot be applied before the timelock passes", async () => {
+      const pool = await program.account.pool.fetch(feePoolPda);
+      await program.methods
+        .proposeReconciliation(pool.totalEscrowed)
+        .accounts(proposeAccounts(operator))
+        .rpc();
+
+      const proposal = await program.account.reconciliationProposal.fetch(proposalPda);
+      assert.equal(proposal.activeEscrowTotal.toString(), pool.totalEscrowed.toString());
+      assert.equal(proposal.executableAt.sub(proposal.proposedAt).toNumber(), 2 * 24 * 60 * 60);
+
+      try {
+        await program.methods
+          .reconcileAccounting()
+          .accounts({
+            admin: operator,
+            program: programId,
+            programData,
+            pool: feePoolPda,
+            poolTokenAccount: getAta(mint, feePoolPda),
+            feeVault: null,
+            proposal: proposalPda,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("Correction should wait for the timelock");
+      } catch (err: any) {
+        assert.include(err.toString(), "TimelockActive");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AT: SOL-Denominated Fees
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AT. SOL-Denominated Fees", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const SOL_FEE = new BN(5_000_000);
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+    let solPoolPda: PublicKey;
+    let transferPda: PublicKey;
+
+    const createSolFeeTransfer = async (memo: string, maxSolFee: BN) => {
+      const nonce = nextNonce();
+      const [pda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, memo, new BN(0), new BN(0), null, new BN(0), null, false, null, null, maxSolFee)
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, solPoolPda, mint, pda))
+        .signers([sender])
+        .rpc();
+      return pda;
+    };
+
+    before(async () => {
+      // A dedicated pool: SOL fee settings are rate limited like the transfer fee
+      const solPoolId = Keypair.generate().publicKey;
+      [solPoolPda] = findPoolPda(programId, solPoolId);
+      await program.methods
+        .initPool(solPoolId, FEE_BPS, "", "")
+        .accounts({
+          operator,
+          mint,
+          pool: solPoolPda,
+          poolTokenAccount: getAta(mint, solPoolPda),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    });
+
+    it("AT1. rejects a SOL fee above the cap", async () => {
+      try {
+        await program.methods
+          .updatePoolConfig({ ...unchanged, feeInSol: true, solFeeLamports: new BN(1_000_000_001) })
+          .accounts({ operator, pool: solPoolPda })
+          .rpc();
+        assert.fail("SOL fee above the cap should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidFeeConfig");
+      }
+    });
+
+    it("AT2. the first SOL fee setup applies immediately, later changes wait the cooldown", async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, feeInSol: true, solFeeLamports: SOL_FEE })
+        .accounts({ operator, pool: solPoolPda })
+        .rpc();
+
+      try {
+        await program.methods
+          .updatePoolConfig({ ...unchanged, solFeeLamports: SOL_FEE.muln(2) })
+          .accounts({ operator, pool: solPoolPda })
+          .rpc();
+        assert.fail("SOL fee change should be rate limited");
+      } catch (err: any) {
+        assert.include(err.toString(), "FeeChangeCooldown");
+      }
+    });
+
+    it("AT3. fails to create when the SOL fee exceeds the sender's maximum", async () => {
+      try {
+        await createSolFeeTransfer("sol fee", SOL_FEE.subn(1));
+        assert.fail("Create should respect the sender's maximum SOL fee");
+      } catch (err: any) {
+        assert.include(err.toString(), "SolFeeTooHigh");
+      }
+    });
+
+    it("AT4. create escrows the SOL fee in the transfer", async () => {
+      const poolLamportsBefore = await connection.getBalance(solPoolPda);
+      transferPda = await createSolFeeTransfer("sol fee", SOL_FEE);
+
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.isTrue(escrow.feePaidInSol);
+      assert.equal(escrow.solFeeLamports.toString(), SOL_FEE.toString());
+      assert.equal(await connection.getBalance(solPoolPda), poolLamportsBefore);
+      const pool = await program.account.pool.fetch(solPoolPda);
+      assert.equal(pool.collectedSolFees.toNumber(), 0);
+    });
+
+    it("AT5. claim pays the full amount and collects the SOL fee", async () => {
+      const poolLamportsBefore = await connection.getBalance(solPoolPda);
+      const balBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, solPoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+
+      const balAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(balAfter.sub(balBefore).toString(), TRANSFER_AMOUNT.toString());
+      assert.equal(await connection.getBalance(solPoolPda) - poolLamportsBefore, SOL_FEE.toNumber());
+      const pool = await program.account.pool.fetch(solPoolPda);
+      assert.equal(pool.collectedFees.toNumber(), 0);
+      assert.equal(pool.collectedSolFees.toString(), SOL_FEE.toString());
+    });
+
+    it("AT6. cancel refunds the SOL fee to the sender", async () => {
+      const pda = await createSolFeeTransfer("sol cancel", SOL_FEE);
+      const escrowLamports = await connection.getBalance(pda);
+      const senderLamportsBefore = await connection.getBalance(sender.publicKey);
+
+      await program.methods
+        .cancelTransfer()
+        .accounts(cancelTransferAccounts(sender.publicKey, solPoolPda, mint, pda))
+        .signers([sender])
+        .rpc();
+
+      assert.equal(await connection.getBalance(sender.publicKey) - senderLamportsBefore, escrowLamports);
+      const pool = await program.account.pool.fetch(solPoolPda);
+      assert.equal(pool.collectedSolFees.toString(), SOL_FEE.toString());
+    });
+
+    it("AT7. reject refunds the SOL fee to the sender", async () => {
+      const pda = await createSolFeeTransfer("sol reject", SOL_FEE);
+      const escrowLamports = await connection.getBalance(pda);
+      const senderLamportsBefore = await connection.getBalance(sender.publicKey);
+
+      await program.methods
+        .rejectTransfer(1)
+        .accounts(rejectTransferAccounts(operator, sender.publicKey, solPoolPda, mint, pda))
+        .rpc();
+
+      assert.equal(await connection.getBalance(sender.publicKey) - senderLamportsBefore, escrowLamports);
+      const pool = await program.account.pool.fetch(solPoolPda);
+      assert.equal(pool.collectedSolFees.toString(), SOL_FEE.toString());
+    });
+
+    it("AT8. fails to withdraw SOL fees as a non-operator", async () => {
+      try {
+        await program.methods
+          .withdrawSolFees()
+          .accounts({ operator: thirdParty.publicKey, pool: solPoolPda })
+          .signers([thirdParty])
+          .rpc();
+        assert.fail("Only the operator should withdraw SOL fees");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+
+    it("AT9. operator withdraws the collected SOL fees", async () => {
+      const poolLamportsBefore = await connection.getBalance(solPoolPda);
+      await program.methods
+        .withdrawSolFees()
+        .accounts({ operator, pool: solPoolPda })
+        .rpc();
+
+      assert.equal(poolLamportsBefore - await connection.getBalance(solPoolPda), SOL_FEE.toNumber());
+      const pool = await program.account.pool.fetch(solPoolPda);
+      assert.equal(pool.collectedSolFees.toNumber(), 0);
+    });
+
+    it("AT10. the net-received quote takes no token fee and quotes the SOL fee", async () => {
+      const quote = await program.methods
+        .quoteNetReceived(TRANSFER_AMOUNT)
+        .accounts({ pool: solPoolPda, mint })
+        .view();
+
+      assert.equal(quote.poolFee.toNumber(), 0);
+      assert.equal(quote.netReceived.toString(), TRANSFER_AMOUNT.toString());
+      assert.equal(quote.solFeeLamports.toString(), SOL_FEE.toString());
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AU: Pool Metadata
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AU. Pool Metadata", () => {
+    let metadataPda: PublicKey;
+
+    before(async () => {
+      [metadataPda] = PublicKey.findProgramAddressSync([Buffer.from("pool_metadata"), feePoolPda.toBuffer()], programId);
+    });
+
+    it("AU1. metadata is set at pool initialization", async () => {
+      const metadata = await program.account.poolMetadata.fetch(metadataPda);
+      assert.equal(metadata.pool.toBase58(), feePoolPda.toBase58());
+      assert.equal(metadata.name, "Fee Pool");
+      assert.equal(metadata.uri, "https://example.com/fee-pool.json");
+    });
+
+    it("AU2. operator updates the metadata", async () => {
+      await program.methods
+        .updatePoolMetadata("Renamed Pool", "https://example.com/renamed.json")
+        .accounts({ operator, pool: feePoolPda, poolMetadata: metadataPda, systemProgram: SystemProgram.programId })
+        .rpc();
+
+      const metadata = await program.account.poolMetadata.fetch(metadataPda);
+      assert.equal(metadata.name, "Renamed Pool");
+      assert.equal(metadata.uri, "https://example.com/renamed.json");
+    });
+
+    it("AU3. fails when the name exceeds 32 bytes", async () => {
+      try {
+        await program.methods
+          .updatePoolMetadata("x".repeat(33), "")
+          .accounts({ operator, pool: feePoolPda, poolMetadata: metadataPda, systemProgram: SystemProgram.programId })
+          .rpc();
+        assert.fail("Overlong name should be rejected");
+      } catch (err: any) {
+        assert.include(err.toString(), "PoolMetadataTooLong");
+      }
+    });
+
+    it("AU4. fails to update as a non-operator", async () => {
+      try {
+        await program.methods
+          .updatePoolMetadata("Hijacked", "")
+          .accounts({ operator: thirdParty.publicKey, pool: feePoolPda, poolMetadata: metadataPda, systemProgram: SystemProgram.programId })
+          .signers([thirdParty])
+          .rpc();
+        assert.fail("Only the operator should update metadata");
+      } catch (err: any) {
+        assert.include(err.toString(), "Unauthorized");
+      }
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AV: Split Refunds
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AV. Split Refunds", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_001);
+    let transferPda: PublicKey;
+
+    function splitAccounts() {
+      return {
+        operator,
+        sender: sender.publicKey,
+        pool: feePoolPda,
+        mint,
+        poolTokenAccount: getAta(mint, feePoolPda),
+        transfer: transferPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        senderProfile: null,
+      };
+    }
+
+    function destinationAccounts() {
+      return [getAta(mint, sender.publicKey), getAta(mint, thirdParty.publicKey)].map((pubkey) => ({
+        pubkey,
+        isSigner: false,
+        isWritable: true,
+      }));
+    }
+
+    before(async () => {
+      const nonce = nextNonce();
+      [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "pooled funds", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+    });
+
+    it("AV1. fails when the shares do not sum to 10000 bps", async () => {
+      try {
+        await program.methods
+          .rejectTransferSplitRefund(null, [
+            { tokenAccount: getAta(mint, sender.publicKey), bps: 5000 },
+            { tokenAccount: getAta(mint, thirdParty.publicKey), bps: 4000 },
+          ])
+          .accounts(splitAccounts())
+          .remainingAccounts(destinationAccounts())
+          .signers([sender])
+          .rpc();
+        assert.fail("Shares must sum to 10000 bps");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidRefundSplit");
+      }
+    });
+
+    it("AV2. splits the refund by share with the remainder to the last destination", async () => {
+      const senderBefore = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      const thirdPartyBefore = await getTokenBalance(connection, getAta(mint, thirdParty.publicKey));
+
+      await program.methods
+        .rejectTransferSplitRefund(null, [
+          { tokenAccount: getAta(mint, sender.publicKey), bps: 3333 },
+          { tokenAccount: getAta(mint, thirdParty.publicKey), bps: 6667 },
+        ])
+        .accounts(splitAccounts())
+        .remainingAccounts(destinationAccounts())
+        .signers([sender])
+        .rpc();
+
+      const senderShare = TRANSFER_AMOUNT.muln(3333).divn(10000);
+      const senderAfter = await getTokenBalance(connection, getAta(mint, sender.publicKey));
+      const thirdPartyAfter = await getTokenBalance(connection, getAta(mint, thirdParty.publicKey));
+      assert.equal(senderAfter.sub(senderBefore).toString(), senderShare.toString());
+      assert.equal(thirdPartyAfter.sub(thirdPartyBefore).toString(), TRANSFER_AMOUNT.sub(senderShare).toString());
+      assert.isNull(await connection.getAccountInfo(transferPda));
+    });
+  });
+
+  // ═══════════════════════════════════════════════════════════════════════════
+  // Group AW: Free Transfers for New Senders
+  // ═══════════════════════════════════════════════════════════════════════════
+
+  describe("AW. Free Transfers for New Senders", () => {
+    const TRANSFER_AMOUNT = new BN(1_000_000);
+    const FREE_TRANSFERS = 2;
+    const unchanged = {
+      transferFeeBps: null,
+      feeBurnBps: null,
+      isPaused: null,
+      deferMissingRefunds: null,
+      allowTransferHooks: null,
+      expiryBehavior: null,
+      eventVerbosity: null,
+      maxLifetimeSeconds: null,
+      staleFeeBps: null,
+      rejectUndoWindow: null,
+      couponSigner: null,
+      allowSelfTransfer: null,
+      minOperatorStake: null,
+      notifyMemo: null,
+      maxExtensionSeconds: null,
+      feeInSol: null,
+      solFeeLamports: null,
+      freeTransferCount: null,
+      feeChangeCooldown: null,
+    };
+    let profilePda: PublicKey;
+
+    /** Create a transfer counted against the sender's profile */
+    async function createWithProfile(poolPda: PublicKey, profile: PublicKey, requiresMutual = false, maxSolFee = new BN(0)): Promise<PublicKey> {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "onboarding", new BN(0), new BN(0), null, new BN(0), null, requiresMutual, null, null, maxSolFee)
+        .accounts({
+          ...createTransferAccounts(sender.publicKey, recipient.publicKey, poolPda, mint, transferPda),
+          senderProfile: profile,
+        })
+        .signers([sender])
+        .rpc();
+      return transferPda;
+    }
+
+    /** Create a transfer with the sender's profile and claim it, returning the fee charged */
+    async function claimWithProfile(): Promise<BN> {
+      const transferPda = await createWithProfile(feePoolPda, profilePda);
+      const balBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      await program.methods
+        .claimTransfer(null)
+        .accounts(claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda))
+        .signers([recipient])
+        .rpc();
+      const balAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      return TRANSFER_AMOUNT.sub(balAfter.sub(balBefore));
+    }
+
+    async function initProfile(poolPda: PublicKey): Promise<PublicKey> {
+      const [pda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sender_profile"), poolPda.toBuffer(), sender.publicKey.toBuffer()],
+        programId
+      );
+      await program.methods
+        .initSenderProfile()
+        .accounts({
+          payer: operator,
+          pool: poolPda,
+          sender: sender.publicKey,
+          senderProfile: pda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      return pda;
+    }
+
+    before(async () => {
+      profilePda = await initProfile(feePoolPda);
+      await program.methods
+        .updatePoolConfig({ ...unchanged, freeTransferCount: FREE_TRANSFERS })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, freeTransferCount: 0 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+    });
+
+    it("AW1. the sender's first transfers are released without a fee", async () => {
+      for (let i = 0; i < FREE_TRANSFERS; i++) {
+        const fee = await claimWithProfile();
+        assert.equal(fee.toNumber(), 0);
+      }
+      const profile = await program.account.senderProfile.fetch(profilePda);
+      assert.equal(profile.freeTransfersUsed, FREE_TRANSFERS);
+    });
+
+    it("AW2. the transfer after the allowance is charged the pool fee", async () => {
+      const pool = await program.account.pool.fetch(feePoolPda);
+      const fee = await claimWithProfile();
+      assert.equal(fee.toString(), TRANSFER_AMOUNT.muln(pool.transferFeeBps).divn(10000).toString());
+      const profile = await program.account.senderProfile.fetch(profilePda);
+      assert.equal(profile.freeTransfersUsed, FREE_TRANSFERS);
+    });
+
+    it("AW3. the waiver decided at creation holds on mutual accept", async () => {
+      await program.methods
+        .updatePoolConfig({ ...unchanged, freeTransferCount: FREE_TRANSFERS + 2 })
+        .accounts({ operator, pool: feePoolPda })
+        .rpc();
+      const transferPda = await createWithProfile(feePoolPda, profilePda, true);
+      assert.isTrue((await program.account.secureTransfer.fetch(transferPda)).feeWaived);
+
+      const balBefore = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      await program.methods
+        .mutualAccept(null)
+        .accounts({
+          ...claimTransferAccounts(recipient.publicKey, sender.publicKey, feePoolPda, mint, transferPda),
+          operator,
+        })
+        .signers([recipient])
+        .rpc();
+      const balAfter = await getTokenBalance(connection, getAta(mint, recipient.publicKey));
+      assert.equal(balAfter.sub(balBefore).toString(), TRANSFER_AMOUNT.toString());
+    });
+
+    it("AW4. a transfer paying its fee in SOL doesn't use up the allowance", async () => {
+      const solPoolId = Keypair.generate().publicKey;
+      const [solPoolPda] = findPoolPda(programId, solPoolId);
+      await program.methods
+        .initPool(solPoolId, FEE_BPS, "", "")
+        .accounts({
+          operator,
+          mint,
+          pool: solPoolPda,
+          poolTokenAccount: getAta(mint, solPoolPda),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      await program.methods
+        .updatePoolConfig({ ...unchanged, feeInSol: true, solFeeLamports: new BN(1_000_000), freeTransferCount: FREE_TRANSFERS })
+        .accounts({ operator, pool: solPoolPda })
+        .rpc();
+      const solProfilePda = await initProfile(solPoolPda);
+
+      const transferPda = await createWithProfile(solPoolPda, solProfilePda, false, new BN(1_000_000));
+      const escrow = await program.account.secureTransfer.fetch(transferPda);
+      assert.isTrue(escrow.feePaidInSol);
+      assert.isFalse(escrow.feeWaived);
+      assert.equal((await program.account.senderProfile.fetch(solProfilePda)).freeTransfersUsed, 0);
+    });
+
+    it("AW5. refunding a fee-waived transfer gives its allowance back", async () => {
+      const usedBefore = (await program.account.senderProfile.fetch(profilePda)).freeTransfersUsed;
+      const transferPda = await createWithProfile(feePoolPda, profilePda);
+      assert.isTrue((await program.account.secureTransfer.fetch(transferPda)).feeWaived);
+      assert.equal((await program.account.senderProfile.fetch(profilePda)).freeTransfersUsed, usedBefore + 1);
+
+      await program.methods
+        .cancelTransfer()
+        .accounts({
+          ...cancelTransferAccounts(sender.publicKey, feePoolPda, mint, transferPda),
+          senderProfile: profilePda,
+        })
+        .signers([sender])
+        .rpc();
+      assert.equal((await program.account.senderProfile.fetch(profilePda)).freeTransfersUsed, usedBefore);
+
+      // The operator rejecting one gives it back too
+      const rejectedPda = await createWithProfile(feePoolPda, profilePda);
+      await program.methods
+        .rejectTransfer(null)
+        .accounts({
+          ...rejectTransferAccounts(operator, sender.publicKey, feePoolPda, mint, rejectedPda),
+          senderProfile: profilePda,
+        })
+        .rpc();
+      assert.equal((await program.account.senderProfile.fetch(profilePda)).freeTransfersUsed, usedBefore);
+    });
+  });
+
+  describe("AX. Account Migration", () => {
+    it("AX1. migrating a pool already in the current layout fails", async () => {
+      try {
+        await program.methods
+          .migratePool()
+          .accounts({ payer: operator, pool: feePoolPda, systemProgram: SystemProgram.programId })
+          .rpc();
+        assert.fail("Current pool should not migrate");
+      } catch (err: any) {
+        assert.include(err.toString(), "AccountAlreadyMigrated");
+      }
+      const pool = await program.account.pool.fetch(feePoolPda);
+      assert.equal(pool.version, 2);
+    });
+
+    it("AX2. migrate_pool rejects accounts that are not pools", async () => {
+      const [metadataPda] = PublicKey.findProgramAddressSync([Buffer.from("pool_metadata"), feePoolPda.toBuffer()], programId);
+      try {
+        await program.methods
+          .migratePool()
+          .accounts({ payer: operator, pool: metadataPda, systemProgram: SystemProgram.programId })
+          .rpc();
+        assert.fail("Pool metadata should not migrate as a pool");
+      } catch (err: any) {
+        assert.include(err.toString(), "InvalidMigrationAccount");
+      }
+    });
+
+    it("AX3. migrating a transfer already in the current layout fails", async () => {
+      const nonce = nextNonce();
+      const [transferPda] = findTransferPda(programId, sender.publicKey, recipient.publicKey, nonce);
+      await program.methods
+        .createTransfer(recipient.publicKey, nonce, TRANSFER_AMOUNT, "migrate", new BN(0), new BN(0), null, new BN(0), null, false, null, null, new BN(0))
+        .accounts(createTransferAccounts(sender.publicKey, recipient.publicKey, feePoolPda, mint, transferPda))
+        .signers([sender])
+        .rpc();
+
+      try {
+        await program.methods
+          .migrateTransfer()
+          .accounts({ payer: operator, transfer: transferPda, systemProgram: SystemProgram.programId })
+          .rpc();
+        assert.fail("Current transfer should not migrate");
+      } catch (err: any) {
+        assert.include(err.toString(), "AccountAlreadyMigrated");
+      }
+      const transfer = await program.account.secureTransfer.fetch(transferPda);
+      assert.equal(transfer.version, 2);
+    });
+  });
 });